@@ -0,0 +1,159 @@
+//! This crate provides `#[derive(AutoHash)]` for the [`autohash`] crate.
+//!
+//! It should be used through the `derive` feature of `autohash`, which
+//! re-exports the macro alongside the trait itself.
+//!
+//! [`autohash`]: https://crates.io/crates/autohash
+
+#![warn(rust_2018_idioms)]
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, GenericParam, Index, Path,
+    Result,
+};
+
+/// Derives `AutoHash` by combining the hashes of every field.
+///
+/// Each field's `get_hash()` is folded into the result with
+/// `autohash::mix::combine`, in declaration order. Enums first combine the
+/// index of the variant, so that different variants with the same fields are
+/// unlikely to collide.
+///
+/// Fields accept these attributes:
+///
+/// - `#[autohash(skip)]`: leave the field out of the hash entirely. This is
+///   always consistent with `Eq`, as long as the remaining fields are.
+/// - `#[autohash(with = path)]`: hash the field by calling `path(&field)`,
+///   which must return a `u64`. This is useful for field types that don't
+///   implement `AutoHash` themselves.
+#[proc_macro_derive(AutoHash, attributes(autohash))]
+pub fn derive_auto_hash(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(mut input: DeriveInput) -> Result<TokenStream> {
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, hashes) = destructure(&data.fields)?;
+            let hash = fold(quote!(0u64), hashes);
+            quote! {
+                let Self #pattern = self;
+                #hash
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::with_capacity(data.variants.len());
+            for (index, variant) in data.variants.iter().enumerate() {
+                let ident = &variant.ident;
+                let index = index as u64;
+                let (pattern, hashes) = destructure(&variant.fields)?;
+                let hash = fold(quote!(0u64), Some(quote!(#index)).into_iter().chain(hashes));
+                arms.push(quote!(Self::#ident #pattern => #hash,));
+            }
+            if arms.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "AutoHash cannot be derived for unions",
+            ));
+        }
+    };
+
+    for param in &mut input.generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(::autohash::AutoHash));
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::autohash::AutoHash for #ident #ty_generics #where_clause {
+            #[inline]
+            fn get_hash(&self) -> u64 {
+                #body
+            }
+        }
+    })
+}
+
+/// Returns a pattern binding all of the hashed fields, and an expression for
+/// each of their hashes.
+fn destructure(fields: &Fields) -> Result<(TokenStream, Vec<TokenStream>)> {
+    let mut bindings = Vec::with_capacity(fields.len());
+    let mut hashes = Vec::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        };
+        let binding = format_ident!("__field{}", i, span = Span::mixed_site());
+        match parse_attrs(&field.attrs)? {
+            FieldAttr::Skip => bindings.push(quote!(#member: _)),
+            FieldAttr::With(path) => {
+                hashes.push(quote!(#path(#binding)));
+                bindings.push(quote!(#member: #binding));
+            }
+            FieldAttr::Default => {
+                hashes.push(quote!(::autohash::AutoHash::get_hash(#binding)));
+                bindings.push(quote!(#member: #binding));
+            }
+        }
+    }
+    Ok((quote!({ #(#bindings,)* .. }), hashes))
+}
+
+/// Combines each of the `hashes` in order, starting from `seed`.
+fn fold(seed: TokenStream, hashes: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+    hashes.into_iter().fold(
+        seed,
+        |seed, hash| quote!(::autohash::mix::combine(#seed, #hash)),
+    )
+}
+
+enum FieldAttr {
+    Default,
+    Skip,
+    With(Path),
+}
+
+fn parse_attrs(attrs: &[syn::Attribute]) -> Result<FieldAttr> {
+    let mut result = FieldAttr::Default;
+    for attr in attrs {
+        if !attr.path().is_ident("autohash") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if !matches!(result, FieldAttr::Default) {
+                return Err(meta.error("conflicting autohash attributes"));
+            }
+            if meta.path.is_ident("skip") {
+                result = FieldAttr::Skip;
+                Ok(())
+            } else if meta.path.is_ident("with") {
+                result = FieldAttr::With(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported autohash attribute"))
+            }
+        })?;
+    }
+    Ok(result)
+}
@@ -0,0 +1,624 @@
+//! Concurrent maps built on `AutoHashMap`.
+//!
+//! Sharing an [`AutoHashMap`] between threads usually means wrapping the whole
+//! map in one lock. Since keys already know their own hash, the
+//! [`ShardedAutoHashMap`] can pick a shard from `get_hash()` alone, without any
+//! hasher state, and then only lock that shard. Operations on keys in different
+//! shards can proceed in parallel.
+//!
+//! References can't outlive a shard's lock, so lookups take a closure that is
+//! called while the lock is held.
+//!
+//! For maps that are read far more often than they change, a
+//! [`ReadMostlyMap`] lets readers use a published table without any locking.
+//! Writers copy the table, apply a batch of changes, and then swap the new
+//! table in atomically. Old tables are freed with epoch-based reclamation once
+//! no reader can still see them.
+//!
+//! This module requires the `std` feature.
+//!
+//! [`AutoHashMap`]: ../map/struct.AutoHashMap.html
+//! [`ShardedAutoHashMap`]: struct.ShardedAutoHashMap.html
+//! [`ReadMostlyMap`]: struct.ReadMostlyMap.html
+
+use crate::map::{AutoHashMap, Entry};
+use crate::mix::{mix64, HashMixer, NoMix};
+use crate::{AutoHash, Equivalent};
+use alloc::boxed::Box;
+use alloc::vec::{self, Vec};
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::Deref;
+use core::slice;
+use core::sync::atomic::Ordering;
+use crossbeam_epoch::{self as epoch, Atomic, Guard, Owned};
+use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The default number of shards in a [`ShardedAutoHashMap`].
+///
+/// [`ShardedAutoHashMap`]: struct.ShardedAutoHashMap.html
+pub const DEFAULT_SHARDS: usize = 16;
+
+/// A concurrent hash map with each shard behind its own `RwLock`.
+///
+/// See the [module documentation] for details.
+///
+/// A panic while a shard is locked does not poison the map, since the shard is
+/// still a valid `AutoHashMap`, though it may be missing the update that was
+/// in progress.
+///
+/// [module documentation]: index.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::concurrent::ShardedAutoHashMap;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let map = Arc::new(ShardedAutoHashMap::new());
+/// let threads: Vec<_> = (0..4)
+///     .map(|t| {
+///         let map = Arc::clone(&map);
+///         thread::spawn(move || {
+///             for i in 0..100 {
+///                 map.entry(i, |entry| *entry.or_insert(0) += t);
+///             }
+///         })
+///     })
+///     .collect();
+/// for thread in threads {
+///     thread.join().unwrap();
+/// }
+///
+/// assert_eq!(map.len(), 100);
+/// assert_eq!(map.get(&42, |&v| v), Some(6));
+/// ```
+pub struct ShardedAutoHashMap<K, V, M = NoMix> {
+    mixer: M,
+    shards: Box<[RwLock<AutoHashMap<K, V, M>>]>,
+}
+
+impl<K, V> ShardedAutoHashMap<K, V, NoMix> {
+    /// Creates an empty `ShardedAutoHashMap` with [`DEFAULT_SHARDS`] shards.
+    ///
+    /// [`DEFAULT_SHARDS`]: constant.DEFAULT_SHARDS.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_mixer(NoMix)
+    }
+
+    /// Creates an empty `ShardedAutoHashMap` with at least `shards` shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_mixer(shards, NoMix)
+    }
+}
+
+impl<K, V, M: Clone> ShardedAutoHashMap<K, V, M> {
+    /// Creates an empty `ShardedAutoHashMap` which will use the given mixer to
+    /// post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self::with_shards_and_mixer(DEFAULT_SHARDS, mixer)
+    }
+
+    /// Creates an empty `ShardedAutoHashMap` with at least `shards` shards,
+    /// which will use the given mixer to post-process the hashes of its keys.
+    ///
+    /// The number of shards is rounded up to a power of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    pub fn with_shards_and_mixer(shards: usize, mixer: M) -> Self {
+        assert!(shards > 0, "the number of shards must be non-zero");
+        let shards = (0..shards.next_power_of_two())
+            .map(|_| RwLock::new(AutoHashMap::with_mixer(mixer.clone())))
+            .collect();
+        Self { mixer, shards }
+    }
+}
+
+impl<K, V, M> ShardedAutoHashMap<K, V, M> {
+    /// Returns a reference to the map's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        &self.mixer
+    }
+
+    /// Returns the number of shards.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the number of elements in the map.
+    ///
+    /// Each shard is counted while it is locked, so this may not match any
+    /// single moment while other threads are modifying the map.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|i| self.read(i).len()).sum()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    ///
+    /// Like [`len`], this checks each shard in turn.
+    ///
+    /// [`len`]: #method.len
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|i| self.read(i).is_empty())
+    }
+
+    /// Clears the map, one shard at a time. Keeps the allocated memory for
+    /// reuse.
+    pub fn clear(&self) {
+        for i in 0..self.shards.len() {
+            self.write(i).clear();
+        }
+    }
+
+    /// Calls `f` for every key-value pair, holding each shard's read lock
+    /// while its entries are visited.
+    ///
+    /// Each shard is a consistent snapshot, but different shards may be
+    /// visited at different moments.
+    pub fn for_each<F>(&self, mut f: F)
+    where
+        F: FnMut(&K, &V),
+    {
+        for i in 0..self.shards.len() {
+            for (k, v) in self.read(i).iter() {
+                f(k, v);
+            }
+        }
+    }
+
+    /// Returns an iterator over cloned key-value pairs, copying one shard at a
+    /// time while holding its read lock.
+    ///
+    /// Each shard is a consistent snapshot, but different shards may be
+    /// copied at different moments. No lock is held between calls to `next`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn snapshot(&self) -> Snapshot<'_, K, V, M>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        Snapshot {
+            shards: self.shards.iter(),
+            current: Vec::new().into_iter(),
+        }
+    }
+
+    /// Consumes the map, returning its shards.
+    pub fn into_shards(self) -> Vec<AutoHashMap<K, V, M>> {
+        Vec::from(self.shards)
+            .into_iter()
+            .map(|lock| lock.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .collect()
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn read(&self, i: usize) -> RwLockReadGuard<'_, AutoHashMap<K, V, M>> {
+        self.shards[i]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn write(&self, i: usize) -> RwLockWriteGuard<'_, AutoHashMap<K, V, M>> {
+        self.shards[i]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K, V, M> ShardedAutoHashMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    /// Returns the index of the shard for a key's own hash.
+    ///
+    /// The mixed hash is mixed again, so the shard is independent of the bits
+    /// that each shard's table uses for its buckets.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn shard_index(&self, hash: u64) -> usize {
+        mix64(self.mixer.mix(hash)) as usize & (self.shards.len() - 1)
+    }
+
+    /// Calls `f` with a reference to the value corresponding to the key, while
+    /// its shard is read-locked, and returns the result.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get<Q: ?Sized, F, R>(&self, k: &Q, f: F) -> Option<R>
+    where
+        Q: AutoHash + Equivalent<K>,
+        F: FnOnce(&V) -> R,
+    {
+        let shard = self.read(self.shard_index(k.get_hash()));
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match shard.get(k) {
+            Some(v) => Some(f(v)),
+            None => None,
+        }
+    }
+
+    /// Calls `f` with a mutable reference to the value corresponding to the
+    /// key, while its shard is write-locked, and returns the result.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_mut<Q: ?Sized, F, R>(&self, k: &Q, f: F) -> Option<R>
+    where
+        Q: AutoHash + Equivalent<K>,
+        F: FnOnce(&mut V) -> R,
+    {
+        let mut shard = self.write(self.shard_index(k.get_hash()));
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match shard.get_mut(k) {
+            Some(v) => Some(f(v)),
+            None => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        self.read(self.shard_index(k.get_hash())).contains_key(k)
+    }
+
+    /// Inserts a key-value pair into the map, returning the old value if the
+    /// key was already present.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(&self, k: K, v: V) -> Option<V> {
+        self.write(self.shard_index(k.get_hash())).insert(k, v)
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove<Q: ?Sized>(&self, k: &Q) -> Option<V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        self.write(self.shard_index(k.get_hash())).remove(k)
+    }
+
+    /// Calls `f` with the key's [`Entry`], while its shard is write-locked,
+    /// and returns the result.
+    ///
+    /// [`Entry`]: ../map/enum.Entry.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn entry<F, R>(&self, key: K, f: F) -> R
+    where
+        F: FnOnce(Entry<'_, K, V, M>) -> R,
+    {
+        let mut shard = self.write(self.shard_index(key.get_hash()));
+        f(shard.entry(key))
+    }
+
+    /// Retains only the elements specified by the predicate, locking one
+    /// shard at a time.
+    pub fn retain<F>(&self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        for i in 0..self.shards.len() {
+            self.write(i).retain(&mut f);
+        }
+    }
+}
+
+impl<K, V, M> Default for ShardedAutoHashMap<K, V, M>
+where
+    M: Clone + Default,
+{
+    /// Creates an empty `ShardedAutoHashMap<K, V, M>`, with the `Default`
+    /// value for the mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::with_mixer(M::default())
+    }
+}
+
+impl<K: Debug, V: Debug, M> Debug for ShardedAutoHashMap<K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for i in 0..self.shards.len() {
+            map.entries(self.read(i).iter());
+        }
+        map.finish()
+    }
+}
+
+/// An iterator over cloned entries of a [`ShardedAutoHashMap`].
+///
+/// This `struct` is created by the [`snapshot`] method on
+/// [`ShardedAutoHashMap`]. See its documentation for more.
+///
+/// [`snapshot`]: struct.ShardedAutoHashMap.html#method.snapshot
+/// [`ShardedAutoHashMap`]: struct.ShardedAutoHashMap.html
+pub struct Snapshot<'a, K, V, M = NoMix> {
+    shards: slice::Iter<'a, RwLock<AutoHashMap<K, V, M>>>,
+    current: vec::IntoIter<(K, V)>,
+}
+
+impl<K: Clone, V: Clone, M> Iterator for Snapshot<'_, K, V, M> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        loop {
+            if let Some(entry) = self.current.next() {
+                return Some(entry);
+            }
+            let shard = self.shards.next()?;
+            let shard = shard.read().unwrap_or_else(PoisonError::into_inner);
+            self.current = shard
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Vec<_>>()
+                .into_iter();
+        }
+    }
+}
+
+impl<K: Clone, V: Clone, M> FusedIterator for Snapshot<'_, K, V, M> {}
+
+impl<K: Debug, V: Debug, M> Debug for Snapshot<'_, K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("current", &self.current.as_slice())
+            .field("remaining_shards", &self.shards.len())
+            .finish()
+    }
+}
+
+/// A concurrent hash map with lock-free readers, for data that rarely changes.
+///
+/// See the [module documentation] for details.
+///
+/// Readers call [`read`] to get a [`ReadGuard`], which dereferences to the
+/// currently published `AutoHashMap`, so lookups use the usual `get` and
+/// `contains_key` methods. The guard sees the same table for as long as it is
+/// held, even if a writer publishes a new one.
+///
+/// Writers are serialized by a mutex. Each call to [`update`] copies the
+/// current table, so changes should be batched into as few updates as
+/// possible.
+///
+/// [module documentation]: index.html
+/// [`read`]: #method.read
+/// [`ReadGuard`]: struct.ReadGuard.html
+/// [`update`]: #method.update
+///
+/// # Examples
+///
+/// ```
+/// use autohash::concurrent::ReadMostlyMap;
+///
+/// let map = ReadMostlyMap::new();
+/// map.update(|m| {
+///     m.insert(1, "a");
+///     m.insert(2, "b");
+/// });
+///
+/// let before = map.read();
+/// map.update(|m| {
+///     m.insert(3, "c");
+/// });
+///
+/// // The old guard still sees the table from before the update.
+/// assert_eq!(before.get(&2), Some(&"b"));
+/// assert!(!before.contains_key(&3));
+/// assert_eq!(map.read().get(&3), Some(&"c"));
+/// ```
+pub struct ReadMostlyMap<K, V, M = NoMix> {
+    table: Atomic<AutoHashMap<K, V, M>>,
+    writer: Mutex<()>,
+}
+
+impl<K, V> ReadMostlyMap<K, V, NoMix> {
+    /// Creates an empty `ReadMostlyMap`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_mixer(NoMix)
+    }
+}
+
+impl<K, V, M> ReadMostlyMap<K, V, M> {
+    /// Creates an empty `ReadMostlyMap` which will use the given mixer to
+    /// post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self::from(AutoHashMap::with_mixer(mixer))
+    }
+
+    /// Returns a guard for the currently published table, without locking.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn read(&self) -> ReadGuard<'_, K, V, M> {
+        let guard = epoch::pin();
+        // The table is never null, and it won't be freed while pinned.
+        let map = unsafe { &*self.table.load(Ordering::Acquire, &guard).as_raw() };
+        ReadGuard { map, _guard: guard }
+    }
+
+    /// Returns the number of elements in the published table.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns `true` if the published table contains no elements.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Consumes the map, returning the published table.
+    pub fn into_inner(mut self) -> AutoHashMap<K, V, M> {
+        let table = mem::replace(&mut self.table, Atomic::null());
+        // We own the only reference to the table, and `Drop` skips null.
+        unsafe { *table.into_owned().into_box() }
+    }
+}
+
+// Old tables are freed by whichever thread collects the epoch garbage, so
+// they must be `Send` even if the map itself never leaves this thread.
+impl<K, V, M> ReadMostlyMap<K, V, M>
+where
+    K: Send + 'static,
+    V: Send + 'static,
+    M: Send + 'static,
+{
+    /// Publishes a new table, replacing the current one.
+    ///
+    /// Readers that already hold a [`ReadGuard`] keep seeing the old table,
+    /// which is freed once they have all released it, possibly on another
+    /// thread. That's why the keys, values, and mixer must be `Send`:
+    ///
+    /// ```compile_fail
+    /// use autohash::concurrent::ReadMostlyMap;
+    /// use autohash::AutoHashMap;
+    /// use std::rc::Rc;
+    ///
+    /// let map = ReadMostlyMap::<Rc<u32>, u32>::new();
+    /// map.publish(AutoHashMap::new());
+    /// ```
+    ///
+    /// [`ReadGuard`]: struct.ReadGuard.html
+    pub fn publish(&self, map: AutoHashMap<K, V, M>) {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        self.swap(map);
+    }
+
+    /// Copies the published table, calls `f` to change the copy, and then
+    /// publishes it, all while holding the writer lock.
+    ///
+    /// If `f` panics, the published table is unchanged.
+    pub fn update<F, R>(&self, f: F) -> R
+    where
+        K: Clone,
+        V: Clone,
+        M: Clone,
+        F: FnOnce(&mut AutoHashMap<K, V, M>) -> R,
+    {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut map = AutoHashMap::clone(&self.read());
+        let result = f(&mut map);
+        self.swap(map);
+        result
+    }
+
+    /// Swaps in a new table, and defers freeing the old one.
+    fn swap(&self, map: AutoHashMap<K, V, M>) {
+        let guard = epoch::pin();
+        let old = self.table.swap(Owned::new(map), Ordering::AcqRel, &guard);
+        // Readers pinned before the swap may still see the old table.
+        unsafe { guard.defer_destroy(old) };
+        guard.flush();
+    }
+}
+
+impl<K, V, M> From<AutoHashMap<K, V, M>> for ReadMostlyMap<K, V, M> {
+    /// Creates a `ReadMostlyMap` that publishes the given table.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn from(map: AutoHashMap<K, V, M>) -> Self {
+        Self {
+            table: Atomic::new(map),
+            writer: Mutex::new(()),
+        }
+    }
+}
+
+impl<K, V, M> Default for ReadMostlyMap<K, V, M>
+where
+    M: Default,
+{
+    /// Creates an empty `ReadMostlyMap<K, V, M>`, with the `Default` value for
+    /// the mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::with_mixer(M::default())
+    }
+}
+
+impl<K, V, M> Drop for ReadMostlyMap<K, V, M> {
+    fn drop(&mut self) {
+        // With `&mut self`, no reader can be holding the current table.
+        unsafe {
+            let table = self.table.load(Ordering::Relaxed, epoch::unprotected());
+            if !table.is_null() {
+                drop(table.into_owned());
+            }
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, M> Debug for ReadMostlyMap<K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.read().fmt(f)
+    }
+}
+
+/// A guard for the table published by a [`ReadMostlyMap`].
+///
+/// This `struct` is created by the [`read`] method on [`ReadMostlyMap`], and
+/// dereferences to the `AutoHashMap` that was published at that time.
+///
+/// Holding a guard delays freeing any table that is replaced in the meantime,
+/// so guards should be short-lived.
+///
+/// [`read`]: struct.ReadMostlyMap.html#method.read
+/// [`ReadMostlyMap`]: struct.ReadMostlyMap.html
+pub struct ReadGuard<'a, K, V, M = NoMix> {
+    map: &'a AutoHashMap<K, V, M>,
+    _guard: Guard,
+}
+
+impl<K, V, M> Deref for ReadGuard<'_, K, V, M> {
+    type Target = AutoHashMap<K, V, M>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn deref(&self) -> &AutoHashMap<K, V, M> {
+        self.map
+    }
+}
+
+impl<K: Debug, V: Debug, M> Debug for ReadGuard<'_, K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
@@ -1,19 +1,60 @@
 use alloc::collections::LinkedList;
 use alloc::vec::Vec;
+use hashbrown::raw::RawTable;
 
 use rayon_dep::iter::{IntoParallelIterator, ParallelIterator};
 
-/// Helper for collecting parallel iterators to an intermediary
-pub(super) fn collect<I: IntoParallelIterator>(iter: I) -> (LinkedList<Vec<I::Item>>, usize) {
+use crate::mix::mix64;
+use crate::AutoHash;
+
+/// Tables of `(hash, key, value)` entries with no keys in common.
+pub(super) type Partitions<K, V> = Vec<RawTable<(u64, K, V)>>;
+
+/// Helper for collecting parallel iterators into tables partitioned by hash.
+///
+/// Each table holds `(hash, key, value)` entries with the key's own hash.
+/// Equal keys always land in the same partition, so the tables have no keys in
+/// common, and each one is deduplicated in parallel. A repeated key keeps the
+/// first key and the last value, just like inserting sequentially.
+///
+/// Returns the tables and their total length.
+pub(super) fn partition<K, V, I>(iter: I) -> (Partitions<K, V>, usize)
+where
+    K: Eq + AutoHash + Send,
+    V: Send,
+    I: IntoParallelIterator<Item = (K, V)>,
+{
+    partition_with(iter, |_, _, value| value)
+}
+
+/// Like `partition`, but calls `f(&key, earlier, later)` to combine the values
+/// of a repeated key, in iterator order.
+pub(super) fn partition_with<K, V, I, F>(iter: I, f: F) -> (Partitions<K, V>, usize)
+where
+    K: Eq + AutoHash + Send,
+    V: Send,
+    I: IntoParallelIterator<Item = (K, V)>,
+    F: Fn(&K, V, V) -> V + Sync,
+{
+    let parts = (rayon_dep::current_num_threads() * 4).next_power_of_two();
+
+    // The partition uses middle bits of the mixed hash, so it doesn't overlap
+    // the low bits for buckets or the high bits for control tags.
+    let part_of = |hash: u64| (mix64(hash) >> 32) as usize & (parts - 1);
+
     let list = iter
         .into_par_iter()
-        .fold(Vec::new, |mut vec, elem| {
-            vec.push(elem);
-            vec
-        })
-        .map(|vec| {
+        .fold(
+            || (0..parts).map(|_| Vec::new()).collect::<Vec<_>>(),
+            |mut vecs, (key, value)| {
+                let hash = key.get_hash();
+                vecs[part_of(hash)].push((hash, key, value));
+                vecs
+            },
+        )
+        .map(|vecs| {
             let mut list = LinkedList::new();
-            list.push_back(vec);
+            list.push_back(vecs);
             list
         })
         .reduce(LinkedList::new, |mut list1, mut list2| {
@@ -21,6 +62,39 @@ pub(super) fn collect<I: IntoParallelIterator>(iter: I) -> (LinkedList<Vec<I::It
             list1
         });
 
-    let len = list.iter().map(Vec::len).sum();
-    (list, len)
+    // Transpose the chunks, keeping each partition in iterator order.
+    let mut chunks: Vec<Vec<Vec<_>>> = (0..parts).map(|_| Vec::new()).collect();
+    for vecs in list {
+        for (part, vec) in chunks.iter_mut().zip(vecs) {
+            if !vec.is_empty() {
+                part.push(vec);
+            }
+        }
+    }
+
+    let tables: Partitions<K, V> = chunks
+        .into_par_iter()
+        .map(|chunks| {
+            let len = chunks.iter().map(Vec::len).sum();
+            let mut table = RawTable::with_capacity(len);
+            for (hash, key, value) in chunks.into_iter().flatten() {
+                let mixed = mix64(hash);
+                match table.find(mixed, |x: &(u64, K, V)| x.0 == hash && x.1 == key) {
+                    Some(bucket) => unsafe {
+                        table.replace_bucket_with(bucket, |(hash, key, earlier)| {
+                            let value = f(&key, earlier, value);
+                            Some((hash, key, value))
+                        });
+                    },
+                    None => {
+                        table.insert_no_grow(mixed, (hash, key, value));
+                    }
+                }
+            }
+            table
+        })
+        .collect();
+
+    let len = tables.iter().map(RawTable::len).sum();
+    (tables, len)
 }
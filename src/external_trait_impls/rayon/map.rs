@@ -1,7 +1,10 @@
 //! Rayon extensions for `AutoHashMap`.
 
+use crate::mix::{HashMixer, NoMix};
 use crate::{AutoHash, AutoHashMap};
+use alloc::vec::Vec;
 use core::fmt;
+use hashbrown::raw::{Bucket, RawTable};
 use rayon_dep::iter::plumbing::UnindexedConsumer;
 use rayon_dep::iter::{
     FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator,
@@ -16,11 +19,11 @@ use rayon_dep::iter::{
 /// [`par_iter`]: /autohash/struct.AutoHashMap.html#method.par_iter
 /// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
 /// [`IntoParallelRefIterator`]: https://docs.rs/rayon/1.0/rayon/iter/trait.IntoParallelRefIterator.html
-pub struct ParIter<'a, K, V> {
-    map: &'a AutoHashMap<K, V>,
+pub struct ParIter<'a, K, V, M = NoMix> {
+    map: &'a AutoHashMap<K, V, M>,
 }
 
-impl<'a, K: Sync, V: Sync> ParallelIterator for ParIter<'a, K, V> {
+impl<'a, K: Sync, V: Sync, M: Sync> ParallelIterator for ParIter<'a, K, V, M> {
     type Item = (&'a K, &'a V);
 
     #[cfg_attr(feature = "inline-more", inline)]
@@ -37,14 +40,14 @@ impl<'a, K: Sync, V: Sync> ParallelIterator for ParIter<'a, K, V> {
     }
 }
 
-impl<K, V> Clone for ParIter<'_, K, V> {
+impl<K, V, M> Clone for ParIter<'_, K, V, M> {
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         ParIter { map: self.map }
     }
 }
 
-impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParIter<'_, K, V> {
+impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug, M> fmt::Debug for ParIter<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.map.iter().fmt(f)
     }
@@ -57,11 +60,11 @@ impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParIter<'_, K,
 ///
 /// [`par_keys`]: /autohash/struct.AutoHashMap.html#method.par_keys
 /// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
-pub struct ParKeys<'a, K, V> {
-    map: &'a AutoHashMap<K, V>,
+pub struct ParKeys<'a, K, V, M = NoMix> {
+    map: &'a AutoHashMap<K, V, M>,
 }
 
-impl<'a, K: Sync, V: Sync> ParallelIterator for ParKeys<'a, K, V> {
+impl<'a, K: Sync, V: Sync, M: Sync> ParallelIterator for ParKeys<'a, K, V, M> {
     type Item = &'a K;
 
     #[cfg_attr(feature = "inline-more", inline)]
@@ -75,14 +78,14 @@ impl<'a, K: Sync, V: Sync> ParallelIterator for ParKeys<'a, K, V> {
     }
 }
 
-impl<K, V> Clone for ParKeys<'_, K, V> {
+impl<K, V, M> Clone for ParKeys<'_, K, V, M> {
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         ParKeys { map: self.map }
     }
 }
 
-impl<K: fmt::Debug + Eq + AutoHash, V> fmt::Debug for ParKeys<'_, K, V> {
+impl<K: fmt::Debug + Eq + AutoHash, V, M> fmt::Debug for ParKeys<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.map.keys().fmt(f)
     }
@@ -95,11 +98,11 @@ impl<K: fmt::Debug + Eq + AutoHash, V> fmt::Debug for ParKeys<'_, K, V> {
 ///
 /// [`par_values`]: /autohash/struct.AutoHashMap.html#method.par_values
 /// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
-pub struct ParValues<'a, K, V> {
-    map: &'a AutoHashMap<K, V>,
+pub struct ParValues<'a, K, V, M = NoMix> {
+    map: &'a AutoHashMap<K, V, M>,
 }
 
-impl<'a, K: Sync, V: Sync> ParallelIterator for ParValues<'a, K, V> {
+impl<'a, K: Sync, V: Sync, M: Sync> ParallelIterator for ParValues<'a, K, V, M> {
     type Item = &'a V;
 
     #[cfg_attr(feature = "inline-more", inline)]
@@ -113,14 +116,14 @@ impl<'a, K: Sync, V: Sync> ParallelIterator for ParValues<'a, K, V> {
     }
 }
 
-impl<K, V> Clone for ParValues<'_, K, V> {
+impl<K, V, M> Clone for ParValues<'_, K, V, M> {
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         ParValues { map: self.map }
     }
 }
 
-impl<K: Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParValues<'_, K, V> {
+impl<K: Eq + AutoHash, V: fmt::Debug, M> fmt::Debug for ParValues<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.map.values().fmt(f)
     }
@@ -135,11 +138,11 @@ impl<K: Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParValues<'_, K, V> {
 /// [`par_iter_mut`]: /autohash/struct.AutoHashMap.html#method.par_iter_mut
 /// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
 /// [`IntoParallelRefMutIterator`]: https://docs.rs/rayon/1.0/rayon/iter/trait.IntoParallelRefMutIterator.html
-pub struct ParIterMut<'a, K, V> {
-    map: &'a mut AutoHashMap<K, V>,
+pub struct ParIterMut<'a, K, V, M = NoMix> {
+    map: &'a mut AutoHashMap<K, V, M>,
 }
 
-impl<'a, K: Send + Sync, V: Send> ParallelIterator for ParIterMut<'a, K, V> {
+impl<'a, K: Send + Sync, V: Send, M: Send> ParallelIterator for ParIterMut<'a, K, V, M> {
     type Item = (&'a K, &'a mut V);
 
     #[cfg_attr(feature = "inline-more", inline)]
@@ -156,7 +159,7 @@ impl<'a, K: Send + Sync, V: Send> ParallelIterator for ParIterMut<'a, K, V> {
     }
 }
 
-impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParIterMut<'_, K, V> {
+impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug, M> fmt::Debug for ParIterMut<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.map.iter().fmt(f)
     }
@@ -169,11 +172,11 @@ impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParIterMut<'_,
 ///
 /// [`par_values_mut`]: /autohash/struct.AutoHashMap.html#method.par_values_mut
 /// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
-pub struct ParValuesMut<'a, K, V> {
-    map: &'a mut AutoHashMap<K, V>,
+pub struct ParValuesMut<'a, K, V, M = NoMix> {
+    map: &'a mut AutoHashMap<K, V, M>,
 }
 
-impl<'a, K: Send, V: Send> ParallelIterator for ParValuesMut<'a, K, V> {
+impl<'a, K: Send, V: Send, M: Send> ParallelIterator for ParValuesMut<'a, K, V, M> {
     type Item = &'a mut V;
 
     #[cfg_attr(feature = "inline-more", inline)]
@@ -187,7 +190,7 @@ impl<'a, K: Send, V: Send> ParallelIterator for ParValuesMut<'a, K, V> {
     }
 }
 
-impl<K: Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParValuesMut<'_, K, V> {
+impl<K: Eq + AutoHash, V: fmt::Debug, M> fmt::Debug for ParValuesMut<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.map.values().fmt(f)
     }
@@ -202,11 +205,11 @@ impl<K: Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParValuesMut<'_, K, V> {
 /// [`into_par_iter`]: /autohash/struct.AutoHashMap.html#method.into_par_iter
 /// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
 /// [`IntoParallelIterator`]: https://docs.rs/rayon/1.0/rayon/iter/trait.IntoParallelIterator.html
-pub struct IntoParIter<K, V> {
-    map: AutoHashMap<K, V>,
+pub struct IntoParIter<K, V, M = NoMix> {
+    map: AutoHashMap<K, V, M>,
 }
 
-impl<K: Send, V: Send> ParallelIterator for IntoParIter<K, V> {
+impl<K: Send, V: Send, M: Send> ParallelIterator for IntoParIter<K, V, M> {
     type Item = (K, V);
 
     #[cfg_attr(feature = "inline-more", inline)]
@@ -218,7 +221,7 @@ impl<K: Send, V: Send> ParallelIterator for IntoParIter<K, V> {
     }
 }
 
-impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug> fmt::Debug for IntoParIter<K, V> {
+impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug, M> fmt::Debug for IntoParIter<K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.map.iter().fmt(f)
     }
@@ -231,11 +234,11 @@ impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug> fmt::Debug for IntoParIter<K,
 ///
 /// [`par_drain`]: /autohash/struct.AutoHashMap.html#method.par_drain
 /// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
-pub struct ParDrain<'a, K, V> {
-    map: &'a mut AutoHashMap<K, V>,
+pub struct ParDrain<'a, K, V, M = NoMix> {
+    map: &'a mut AutoHashMap<K, V, M>,
 }
 
-impl<K: Send, V: Send> ParallelIterator for ParDrain<'_, K, V> {
+impl<K: Send, V: Send, M: Send> ParallelIterator for ParDrain<'_, K, V, M> {
     type Item = (K, V);
 
     #[cfg_attr(feature = "inline-more", inline)]
@@ -247,45 +250,187 @@ impl<K: Send, V: Send> ParallelIterator for ParDrain<'_, K, V> {
     }
 }
 
-impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug> fmt::Debug for ParDrain<'_, K, V> {
+impl<K: fmt::Debug + Eq + AutoHash, V: fmt::Debug, M> fmt::Debug for ParDrain<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.map.iter().fmt(f)
     }
 }
 
-impl<K: Sync, V: Sync> AutoHashMap<K, V> {
+/// Parallel iterator over entries removed from a map by a predicate.
+///
+/// This iterator is created by the [`par_drain_filter`] method on [`AutoHashMap`].
+/// See its documentation for more.
+///
+/// [`par_drain_filter`]: /autohash/struct.AutoHashMap.html#method.par_drain_filter
+/// [`AutoHashMap`]: /autohash/struct.AutoHashMap.html
+pub struct ParDrainFilter<K, V> {
+    pub(super) entries: Vec<(K, V)>,
+}
+
+impl<K: Send, V: Send> ParallelIterator for ParDrainFilter<K, V> {
+    type Item = (K, V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.entries.into_par_iter().drive_unindexed(consumer)
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ParDrainFilter<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.entries.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+
+impl<K: Sync, V: Sync, M: Sync> AutoHashMap<K, V, M> {
     /// Visits (potentially in parallel) immutably borrowed keys in an arbitrary order.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_keys(&self) -> ParKeys<'_, K, V> {
+    pub fn par_keys(&self) -> ParKeys<'_, K, V, M> {
         ParKeys { map: self }
     }
 
     /// Visits (potentially in parallel) immutably borrowed values in an arbitrary order.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_values(&self) -> ParValues<'_, K, V> {
+    pub fn par_values(&self) -> ParValues<'_, K, V, M> {
         ParValues { map: self }
     }
 }
 
-impl<K: Send, V: Send> AutoHashMap<K, V> {
+impl<K: Send, V: Send, M: Send> AutoHashMap<K, V, M> {
     /// Visits (potentially in parallel) mutably borrowed values in an arbitrary order.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_values_mut(&mut self) -> ParValuesMut<'_, K, V> {
+    pub fn par_values_mut(&mut self) -> ParValuesMut<'_, K, V, M> {
         ParValuesMut { map: self }
     }
 
     /// Consumes (potentially in parallel) all values in an arbitrary order,
     /// while preserving the map's allocated memory for reuse.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_drain(&mut self) -> ParDrain<'_, K, V> {
+    pub fn par_drain(&mut self) -> ParDrain<'_, K, V, M> {
         ParDrain { map: self }
     }
 }
 
-impl<K, V> AutoHashMap<K, V>
+impl<K: Send + Sync, V: Send, M: Send> AutoHashMap<K, V, M> {
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k, &mut v)`
+    /// returns `false`.
+    ///
+    /// The predicate is evaluated (potentially in parallel) across the table,
+    /// and then the rejected entries are erased sequentially.
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        F: Fn(&K, &mut V) -> bool + Sync,
+    {
+        let buckets = self.par_find_buckets(|k, v| !f(k, v));
+        for bucket in buckets {
+            unsafe { self.table.erase(bucket) };
+        }
+    }
+
+    /// Removes all entries which are true under the given predicate,
+    /// and returns a parallel iterator over the removed entries.
+    ///
+    /// In other words, move all pairs `(k, v)` such that `f(&k, &mut v)`
+    /// returns `true` out into another iterator.
+    ///
+    /// The predicate is evaluated (potentially in parallel) across the table,
+    /// and then the matching entries are moved out sequentially, before this
+    /// method returns. They are removed from the map even if the returned
+    /// iterator is not used.
+    pub fn par_drain_filter<F>(&mut self, f: F) -> ParDrainFilter<K, V>
+    where
+        F: Fn(&K, &mut V) -> bool + Sync,
+    {
+        let buckets = self.par_find_buckets(f);
+        let entries = buckets
+            .into_iter()
+            .map(|bucket| unsafe { self.table.remove(bucket) })
+            .collect();
+        ParDrainFilter { entries }
+    }
+
+    /// Finds (potentially in parallel) the buckets matching a predicate.
+    fn par_find_buckets<F>(&mut self, f: F) -> Vec<Bucket<(K, V)>>
+    where
+        F: Fn(&K, &mut V) -> bool + Sync,
+    {
+        unsafe { self.table.par_iter() }
+            .filter(|x| unsafe {
+                let r = x.as_mut();
+                f(&r.0, &mut r.1)
+            })
+            .collect()
+    }
+}
+
+impl<K, V, M> AutoHashMap<K, V, M>
+where
+    K: Eq + AutoHash + Send,
+    V: Send,
+    M: HashMixer + Default + Send,
+{
+    /// Merges (potentially in parallel) many maps into one, calling `f` to
+    /// combine the values of any key that is present in more than one.
+    ///
+    /// The values are passed as `f(&key, earlier, later)`, in the order of
+    /// the maps in the iterator, and the first map's copy of the key is kept.
+    ///
+    /// The other maps are merged into the first one, which keeps its mixer,
+    /// and its keys are only hashed again if it has to grow. The keys of the
+    /// other maps are partitioned by hash, so each of them calls `get_hash`
+    /// only once, and only keys in the same partition are ever compared. An
+    /// empty iterator returns an empty map with the default mixer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    ///
+    /// let maps: Vec<AutoHashMap<u32, u32>> = (0..4)
+    ///     .map(|i| (i..i + 10).map(|k| (k, 1)).collect())
+    ///     .collect();
+    ///
+    /// let counts: AutoHashMap<u32, u32> = AutoHashMap::par_merge(maps, |_, a, b| a + b);
+    /// assert_eq!(counts.len(), 13);
+    /// assert_eq!(counts[&0], 1);
+    /// assert_eq!(counts[&5], 4);
+    /// ```
+    pub fn par_merge<I, F>(maps: I, f: F) -> Self
+    where
+        I: IntoParallelIterator<Item = Self>,
+        F: Fn(&K, V, V) -> V + Sync,
+    {
+        let mut maps: Vec<Self> = maps.into_par_iter().collect();
+        if maps.is_empty() {
+            return AutoHashMap::with_mixer(M::default());
+        }
+        let mut map = maps.remove(0);
+        let entries = maps
+            .into_par_iter()
+            .flat_map(IntoParallelIterator::into_par_iter);
+        let (tables, len) = super::helpers::partition_with(entries, &f);
+        merge_with(
+            &mut map,
+            tables.into_iter().flat_map(RawTable::into_iter),
+            len,
+            f,
+        );
+        map
+    }
+}
+
+impl<K, V, M> AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash + Sync,
     V: PartialEq + Sync,
+    M: HashMixer + Sync,
 {
     /// Returns `true` if the map is equal to another,
     /// i.e. both maps contain the same keys mapped to the same values.
@@ -295,13 +440,13 @@ where
         self.len() == other.len()
             && self
                 .into_par_iter()
-                .all(|(key, value)| other.get(key).map_or(false, |v| *value == *v))
+                .all(|(key, value)| other.get(key) == Some(value))
     }
 }
 
-impl<K: Send, V: Send> IntoParallelIterator for AutoHashMap<K, V> {
+impl<K: Send, V: Send, M: Send> IntoParallelIterator for AutoHashMap<K, V, M> {
     type Item = (K, V);
-    type Iter = IntoParIter<K, V>;
+    type Iter = IntoParIter<K, V, M>;
 
     #[cfg_attr(feature = "inline-more", inline)]
     fn into_par_iter(self) -> Self::Iter {
@@ -309,9 +454,9 @@ impl<K: Send, V: Send> IntoParallelIterator for AutoHashMap<K, V> {
     }
 }
 
-impl<'a, K: Sync, V: Sync> IntoParallelIterator for &'a AutoHashMap<K, V> {
+impl<'a, K: Sync, V: Sync, M: Sync> IntoParallelIterator for &'a AutoHashMap<K, V, M> {
     type Item = (&'a K, &'a V);
-    type Iter = ParIter<'a, K, V>;
+    type Iter = ParIter<'a, K, V, M>;
 
     #[cfg_attr(feature = "inline-more", inline)]
     fn into_par_iter(self) -> Self::Iter {
@@ -319,9 +464,9 @@ impl<'a, K: Sync, V: Sync> IntoParallelIterator for &'a AutoHashMap<K, V> {
     }
 }
 
-impl<'a, K: Send + Sync, V: Send> IntoParallelIterator for &'a mut AutoHashMap<K, V> {
+impl<'a, K: Send + Sync, V: Send, M: Send> IntoParallelIterator for &'a mut AutoHashMap<K, V, M> {
     type Item = (&'a K, &'a mut V);
-    type Iter = ParIterMut<'a, K, V>;
+    type Iter = ParIterMut<'a, K, V, M>;
 
     #[cfg_attr(feature = "inline-more", inline)]
     fn into_par_iter(self) -> Self::Iter {
@@ -333,26 +478,31 @@ impl<'a, K: Send + Sync, V: Send> IntoParallelIterator for &'a mut AutoHashMap<K
 /// hashmap. If multiple pairs correspond to the same key, then the
 /// ones produced earlier in the parallel iterator will be
 /// overwritten, just as with a sequential iterator.
-impl<K, V> FromParallelIterator<(K, V)> for AutoHashMap<K, V>
+///
+/// The map uses the default mixer, like `FromIterator`. To collect with
+/// another mixer, `par_extend` a map created with it.
+impl<K, V, M> FromParallelIterator<(K, V)> for AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash + Send,
     V: Send,
+    M: HashMixer + Default,
 {
     fn from_par_iter<P>(par_iter: P) -> Self
     where
         P: IntoParallelIterator<Item = (K, V)>,
     {
-        let mut map = AutoHashMap::default();
+        let mut map = AutoHashMap::with_mixer(M::default());
         map.par_extend(par_iter);
         map
     }
 }
 
 /// Extend a hash map with items from a parallel iterator.
-impl<K, V> ParallelExtend<(K, V)> for AutoHashMap<K, V>
+impl<K, V, M> ParallelExtend<(K, V)> for AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash + Send,
     V: Send,
+    M: HashMixer,
 {
     fn par_extend<I>(&mut self, par_iter: I)
     where
@@ -363,36 +513,73 @@ where
 }
 
 /// Extend a hash map with copied items from a parallel iterator.
-impl<'a, K, V> ParallelExtend<(&'a K, &'a V)> for AutoHashMap<K, V>
+impl<'a, K, V, M> ParallelExtend<(&'a K, &'a V)> for AutoHashMap<K, V, M>
 where
     K: Copy + Eq + AutoHash + Sync,
     V: Copy + Sync,
+    M: HashMixer,
 {
     fn par_extend<I>(&mut self, par_iter: I)
     where
         I: IntoParallelIterator<Item = (&'a K, &'a V)>,
     {
-        extend(self, par_iter);
+        let (tables, len) = super::helpers::partition(par_iter);
+        let entries = tables.into_iter().flat_map(RawTable::into_iter);
+        merge(self, entries.map(|(hash, &k, &v)| (hash, k, v)), len);
     }
 }
 
-// This is equal to the normal `HashMap` -- no custom advantage.
-fn extend<K, V, I>(map: &mut AutoHashMap<K, V>, par_iter: I)
+// Keys are partitioned by their own hash, so they are deduplicated in
+// parallel, and then merged without calling `get_hash` again.
+fn extend<K, V, M, I>(map: &mut AutoHashMap<K, V, M>, par_iter: I)
+where
+    K: Eq + AutoHash + Send,
+    V: Send,
+    M: HashMixer,
+    I: IntoParallelIterator<Item = (K, V)>,
+{
+    let (tables, len) = super::helpers::partition(par_iter);
+    merge(map, tables.into_iter().flat_map(RawTable::into_iter), len);
+}
+
+/// Inserts `(hash, key, value)` entries that have no keys in common.
+pub(super) fn merge<K, V, M, I>(map: &mut AutoHashMap<K, V, M>, entries: I, len: usize)
 where
     K: Eq + AutoHash,
-    I: IntoParallelIterator,
-    AutoHashMap<K, V>: Extend<I::Item>,
+    M: HashMixer,
+    I: Iterator<Item = (u64, K, V)>,
 {
-    let (list, len) = super::helpers::collect(par_iter);
-
-    // Keys may be already present or show multiple times in the iterator.
-    // Reserve the entire length if the map is empty.
-    // Otherwise reserve half the length (rounded up), so the map
-    // will only resize twice in the worst case.
-    let reserve = if map.is_empty() { len } else { (len + 1) / 2 };
-    map.reserve(reserve);
-    for vec in list {
-        map.extend(vec);
+    merge_with(map, entries, len, |_, _, value| value);
+}
+
+/// Like `merge`, but calls `f(&key, old, new)` to combine the values of a key
+/// that was already in the map.
+fn merge_with<K, V, M, I, F>(map: &mut AutoHashMap<K, V, M>, entries: I, len: usize, f: F)
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+    I: Iterator<Item = (u64, K, V)>,
+    F: Fn(&K, V, V) -> V,
+{
+    // The entries are already unique, so only keys that were in the map
+    // before need to be compared, and reserving all of them can only
+    // over-allocate for those.
+    let was_empty = map.is_empty();
+    map.reserve(len);
+    for (hash, key, value) in entries {
+        let hash = map.mixer.mix(hash);
+        if !was_empty {
+            if let Some(bucket) = map.table.find(hash, |x| x.0 == key) {
+                unsafe {
+                    map.table.replace_bucket_with(bucket, |(key, old)| {
+                        let value = f(&key, old, value);
+                        Some((key, value))
+                    });
+                }
+                continue;
+            }
+        }
+        map.table.insert_no_grow(hash, (key, value));
     }
 }
 
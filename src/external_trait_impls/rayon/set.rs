@@ -1,6 +1,10 @@
 //! Rayon extensions for `AutoHashSet`.
 
+use super::map;
+use crate::mix::{HashMixer, NoMix};
 use crate::{AutoHash, AutoHashSet};
+use core::fmt;
+use hashbrown::raw::RawTable;
 use rayon_dep::iter::plumbing::UnindexedConsumer;
 use rayon_dep::iter::{
     FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator,
@@ -15,11 +19,11 @@ use rayon_dep::iter::{
 /// [`into_par_iter`]: /autohash/struct.AutoHashSet.html#method.into_par_iter
 /// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
 /// [`IntoParallelIterator`]: https://docs.rs/rayon/1.0/rayon/iter/trait.IntoParallelIterator.html
-pub struct IntoParIter<T> {
-    set: AutoHashSet<T>,
+pub struct IntoParIter<T, M = NoMix> {
+    set: AutoHashSet<T, M>,
 }
 
-impl<T: Send> ParallelIterator for IntoParIter<T> {
+impl<T: Send, M: Send> ParallelIterator for IntoParIter<T, M> {
     type Item = T;
 
     fn drive_unindexed<C>(self, consumer: C) -> C::Result
@@ -41,11 +45,11 @@ impl<T: Send> ParallelIterator for IntoParIter<T> {
 ///
 /// [`par_drain`]: /autohash/struct.AutoHashSet.html#method.par_drain
 /// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
-pub struct ParDrain<'a, T> {
-    set: &'a mut AutoHashSet<T>,
+pub struct ParDrain<'a, T, M = NoMix> {
+    set: &'a mut AutoHashSet<T, M>,
 }
 
-impl<T: Send> ParallelIterator for ParDrain<'_, T> {
+impl<T: Send, M: Send> ParallelIterator for ParDrain<'_, T, M> {
     type Item = T;
 
     fn drive_unindexed<C>(self, consumer: C) -> C::Result
@@ -60,6 +64,36 @@ impl<T: Send> ParallelIterator for ParDrain<'_, T> {
     }
 }
 
+/// Parallel iterator over elements removed from a set by a predicate.
+///
+/// This iterator is created by the [`par_drain_filter`] method on [`AutoHashSet`].
+/// See its documentation for more.
+///
+/// [`par_drain_filter`]: /autohash/struct.AutoHashSet.html#method.par_drain_filter
+/// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
+pub struct ParDrainFilter<T> {
+    inner: map::ParDrainFilter<T, ()>,
+}
+
+impl<T: Send> ParallelIterator for ParDrainFilter<T> {
+    type Item = T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.inner.map(|(k, _)| k).drive_unindexed(consumer)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ParDrainFilter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.inner.entries.iter().map(|(k, _)| k))
+            .finish()
+    }
+}
+
 /// Parallel iterator over shared references to elements in a set.
 ///
 /// This iterator is created by the [`par_iter`] method on [`AutoHashSet`]
@@ -69,11 +103,11 @@ impl<T: Send> ParallelIterator for ParDrain<'_, T> {
 /// [`par_iter`]: /autohash/struct.AutoHashSet.html#method.par_iter
 /// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
 /// [`IntoParallelRefIterator`]: https://docs.rs/rayon/1.0/rayon/iter/trait.IntoParallelRefIterator.html
-pub struct ParIter<'a, T> {
-    set: &'a AutoHashSet<T>,
+pub struct ParIter<'a, T, M = NoMix> {
+    set: &'a AutoHashSet<T, M>,
 }
 
-impl<'a, T: Sync> ParallelIterator for ParIter<'a, T> {
+impl<'a, T: Sync, M: Sync> ParallelIterator for ParIter<'a, T, M> {
     type Item = &'a T;
 
     fn drive_unindexed<C>(self, consumer: C) -> C::Result
@@ -92,14 +126,15 @@ impl<'a, T: Sync> ParallelIterator for ParIter<'a, T> {
 ///
 /// [`par_difference`]: /autohash/struct.AutoHashSet.html#method.par_difference
 /// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
-pub struct ParDifference<'a, T> {
-    a: &'a AutoHashSet<T>,
-    b: &'a AutoHashSet<T>,
+pub struct ParDifference<'a, T, M = NoMix> {
+    a: &'a AutoHashSet<T, M>,
+    b: &'a AutoHashSet<T, M>,
 }
 
-impl<'a, T> ParallelIterator for ParDifference<'a, T>
+impl<'a, T, M> ParallelIterator for ParDifference<'a, T, M>
 where
     T: Eq + AutoHash + Sync,
+    M: HashMixer + Sync,
 {
     type Item = &'a T;
 
@@ -123,14 +158,15 @@ where
 ///
 /// [`par_symmetric_difference`]: /autohash/struct.AutoHashSet.html#method.par_symmetric_difference
 /// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
-pub struct ParSymmetricDifference<'a, T> {
-    a: &'a AutoHashSet<T>,
-    b: &'a AutoHashSet<T>,
+pub struct ParSymmetricDifference<'a, T, M = NoMix> {
+    a: &'a AutoHashSet<T, M>,
+    b: &'a AutoHashSet<T, M>,
 }
 
-impl<'a, T> ParallelIterator for ParSymmetricDifference<'a, T>
+impl<'a, T, M> ParallelIterator for ParSymmetricDifference<'a, T, M>
 where
     T: Eq + AutoHash + Sync,
+    M: HashMixer + Sync,
 {
     type Item = &'a T;
 
@@ -153,14 +189,15 @@ where
 ///
 /// [`par_intersection`]: /autohash/struct.AutoHashSet.html#method.par_intersection
 /// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
-pub struct ParIntersection<'a, T> {
-    a: &'a AutoHashSet<T>,
-    b: &'a AutoHashSet<T>,
+pub struct ParIntersection<'a, T, M = NoMix> {
+    a: &'a AutoHashSet<T, M>,
+    b: &'a AutoHashSet<T, M>,
 }
 
-impl<'a, T> ParallelIterator for ParIntersection<'a, T>
+impl<'a, T, M> ParallelIterator for ParIntersection<'a, T, M>
 where
     T: Eq + AutoHash + Sync,
+    M: HashMixer + Sync,
 {
     type Item = &'a T;
 
@@ -182,14 +219,15 @@ where
 ///
 /// [`par_union`]: /autohash/struct.AutoHashSet.html#method.par_union
 /// [`AutoHashSet`]: /autohash/struct.AutoHashSet.html
-pub struct ParUnion<'a, T> {
-    a: &'a AutoHashSet<T>,
-    b: &'a AutoHashSet<T>,
+pub struct ParUnion<'a, T, M = NoMix> {
+    a: &'a AutoHashSet<T, M>,
+    b: &'a AutoHashSet<T, M>,
 }
 
-impl<'a, T> ParallelIterator for ParUnion<'a, T>
+impl<'a, T, M> ParallelIterator for ParUnion<'a, T, M>
 where
     T: Eq + AutoHash + Sync,
+    M: HashMixer + Sync,
 {
     type Item = &'a T;
 
@@ -204,14 +242,15 @@ where
     }
 }
 
-impl<T> AutoHashSet<T>
+impl<T, M> AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Sync,
+    M: HashMixer + Sync,
 {
     /// Visits (potentially in parallel) the values representing the difference,
     /// i.e. the values that are in `self` but not in `other`.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_difference<'a>(&'a self, other: &'a Self) -> ParDifference<'a, T> {
+    pub fn par_difference<'a>(&'a self, other: &'a Self) -> ParDifference<'a, T, M> {
         ParDifference { a: self, b: other }
     }
 
@@ -221,21 +260,21 @@ where
     pub fn par_symmetric_difference<'a>(
         &'a self,
         other: &'a Self,
-    ) -> ParSymmetricDifference<'a, T> {
+    ) -> ParSymmetricDifference<'a, T, M> {
         ParSymmetricDifference { a: self, b: other }
     }
 
     /// Visits (potentially in parallel) the values representing the
     /// intersection, i.e. the values that are both in `self` and `other`.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_intersection<'a>(&'a self, other: &'a Self) -> ParIntersection<'a, T> {
+    pub fn par_intersection<'a>(&'a self, other: &'a Self) -> ParIntersection<'a, T, M> {
         ParIntersection { a: self, b: other }
     }
 
     /// Visits (potentially in parallel) the values representing the union,
     /// i.e. all the values in `self` or `other`, without duplicates.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_union<'a>(&'a self, other: &'a Self) -> ParUnion<'a, T> {
+    pub fn par_union<'a>(&'a self, other: &'a Self) -> ParUnion<'a, T, M> {
         ParUnion { a: self, b: other }
     }
 
@@ -276,21 +315,53 @@ where
     }
 }
 
-impl<T> AutoHashSet<T>
+impl<T, M> AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Send,
+    M: HashMixer + Send,
 {
     /// Consumes (potentially in parallel) all values in an arbitrary order,
     /// while preserving the set's allocated memory for reuse.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn par_drain(&mut self) -> ParDrain<'_, T> {
+    pub fn par_drain(&mut self) -> ParDrain<'_, T, M> {
         ParDrain { set: self }
     }
 }
 
-impl<T: Send> IntoParallelIterator for AutoHashSet<T> {
+impl<T: Send + Sync, M: Send> AutoHashSet<T, M> {
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    ///
+    /// The predicate is evaluated (potentially in parallel) across the table,
+    /// and then the rejected elements are erased sequentially.
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.map.par_retain(|k, _| f(k));
+    }
+
+    /// Removes all elements which are true under the given predicate,
+    /// and returns a parallel iterator over the removed elements.
+    ///
+    /// The predicate is evaluated (potentially in parallel) across the table,
+    /// and then the matching elements are moved out sequentially, before this
+    /// method returns. They are removed from the set even if the returned
+    /// iterator is not used.
+    pub fn par_drain_filter<F>(&mut self, f: F) -> ParDrainFilter<T>
+    where
+        F: Fn(&T) -> bool + Sync,
+    {
+        ParDrainFilter {
+            inner: self.map.par_drain_filter(|k, _| f(k)),
+        }
+    }
+}
+
+impl<T: Send, M: Send> IntoParallelIterator for AutoHashSet<T, M> {
     type Item = T;
-    type Iter = IntoParIter<T>;
+    type Iter = IntoParIter<T, M>;
 
     #[cfg_attr(feature = "inline-more", inline)]
     fn into_par_iter(self) -> Self::Iter {
@@ -298,9 +369,9 @@ impl<T: Send> IntoParallelIterator for AutoHashSet<T> {
     }
 }
 
-impl<'a, T: Sync> IntoParallelIterator for &'a AutoHashSet<T> {
+impl<'a, T: Sync, M: Sync> IntoParallelIterator for &'a AutoHashSet<T, M> {
     type Item = &'a T;
-    type Iter = ParIter<'a, T>;
+    type Iter = ParIter<'a, T, M>;
 
     #[cfg_attr(feature = "inline-more", inline)]
     fn into_par_iter(self) -> Self::Iter {
@@ -309,63 +380,57 @@ impl<'a, T: Sync> IntoParallelIterator for &'a AutoHashSet<T> {
 }
 
 /// Collect values from a parallel iterator into a hashset.
-impl<T> FromParallelIterator<T> for AutoHashSet<T>
+///
+/// The set uses the default mixer, like `FromIterator`. To collect with
+/// another mixer, `par_extend` a set created with it.
+impl<T, M> FromParallelIterator<T> for AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Send,
+    M: HashMixer + Default,
 {
     fn from_par_iter<P>(par_iter: P) -> Self
     where
         P: IntoParallelIterator<Item = T>,
     {
-        let mut set = AutoHashSet::default();
+        let mut set = AutoHashSet::with_mixer(M::default());
         set.par_extend(par_iter);
         set
     }
 }
 
 /// Extend a hash set with items from a parallel iterator.
-impl<T> ParallelExtend<T> for AutoHashSet<T>
+impl<T, M> ParallelExtend<T> for AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Send,
+    M: HashMixer,
 {
     fn par_extend<I>(&mut self, par_iter: I)
     where
         I: IntoParallelIterator<Item = T>,
     {
-        extend(self, par_iter);
+        self.map
+            .par_extend(par_iter.into_par_iter().map(|x| (x, ())));
     }
 }
 
 /// Extend a hash set with copied items from a parallel iterator.
-impl<'a, T> ParallelExtend<&'a T> for AutoHashSet<T>
+impl<'a, T, M> ParallelExtend<&'a T> for AutoHashSet<T, M>
 where
     T: 'a + Copy + Eq + AutoHash + Sync,
+    M: HashMixer,
 {
     fn par_extend<I>(&mut self, par_iter: I)
     where
         I: IntoParallelIterator<Item = &'a T>,
     {
-        extend(self, par_iter);
-    }
-}
-
-// This is equal to the normal `HashSet` -- no custom advantage.
-fn extend<T, I>(set: &mut AutoHashSet<T>, par_iter: I)
-where
-    T: Eq + AutoHash,
-    I: IntoParallelIterator,
-    AutoHashSet<T>: Extend<I::Item>,
-{
-    let (list, len) = super::helpers::collect(par_iter);
-
-    // Values may be already present or show multiple times in the iterator.
-    // Reserve the entire length if the set is empty.
-    // Otherwise reserve half the length (rounded up), so the set
-    // will only resize twice in the worst case.
-    let reserve = if set.is_empty() { len } else { (len + 1) / 2 };
-    set.reserve(reserve);
-    for vec in list {
-        set.extend(vec);
+        let par_iter = par_iter.into_par_iter().map(|x| (x, ()));
+        let (tables, len) = super::helpers::partition(par_iter);
+        let entries = tables.into_iter().flat_map(RawTable::into_iter);
+        map::merge(
+            &mut self.map,
+            entries.map(|(hash, &x, ())| (hash, x, ())),
+            len,
+        );
     }
 }
 
@@ -1,11 +1,11 @@
-mod size_hint {
+pub(crate) mod size_hint {
     use core::cmp;
 
     /// This presumably exists to prevent denial of service attacks.
     ///
     /// Original discussion: https://github.com/serde-rs/serde/issues/1114.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub(super) fn cautious(hint: Option<usize>) -> usize {
+    pub(crate) fn cautious(hint: Option<usize>) -> usize {
         cmp::min(hint.unwrap_or(0), 4096)
     }
 }
@@ -16,14 +16,16 @@ mod map {
     use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
     use serde::ser::{Serialize, Serializer};
 
+    use crate::mix::HashMixer;
     use crate::{AutoHash, AutoHashMap};
 
     use super::size_hint;
 
-    impl<K, V> Serialize for AutoHashMap<K, V>
+    impl<K, V, M> Serialize for AutoHashMap<K, V, M>
     where
         K: Serialize + Eq + AutoHash,
         V: Serialize,
+        M: HashMixer,
     {
         #[cfg_attr(feature = "inline-more", inline)]
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -34,25 +36,27 @@ mod map {
         }
     }
 
-    impl<'de, K, V> Deserialize<'de> for AutoHashMap<K, V>
+    impl<'de, K, V, M> Deserialize<'de> for AutoHashMap<K, V, M>
     where
         K: Deserialize<'de> + Eq + AutoHash,
         V: Deserialize<'de>,
+        M: HashMixer + Default,
     {
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
             D: Deserializer<'de>,
         {
-            struct MapVisitor<K, V> {
-                marker: PhantomData<AutoHashMap<K, V>>,
+            struct MapVisitor<K, V, M> {
+                marker: PhantomData<AutoHashMap<K, V, M>>,
             }
 
-            impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+            impl<'de, K, V, M> Visitor<'de> for MapVisitor<K, V, M>
             where
                 K: Deserialize<'de> + Eq + AutoHash,
                 V: Deserialize<'de>,
+                M: HashMixer + Default,
             {
-                type Value = AutoHashMap<K, V>;
+                type Value = AutoHashMap<K, V, M>;
 
                 fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
                     formatter.write_str("a map")
@@ -63,8 +67,10 @@ mod map {
                 where
                     A: MapAccess<'de>,
                 {
-                    let mut values =
-                        AutoHashMap::with_capacity(size_hint::cautious(map.size_hint()));
+                    let mut values = AutoHashMap::with_capacity_and_mixer(
+                        size_hint::cautious(map.size_hint()),
+                        M::default(),
+                    );
 
                     while let Some((key, value)) = map.next_entry()? {
                         values.insert(key, value);
@@ -79,6 +85,43 @@ mod map {
             };
             deserializer.deserialize_map(visitor)
         }
+
+        fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct MapInPlaceVisitor<'a, K, V, M>(&'a mut AutoHashMap<K, V, M>);
+
+            impl<'a, 'de, K, V, M> Visitor<'de> for MapInPlaceVisitor<'a, K, V, M>
+            where
+                K: Deserialize<'de> + Eq + AutoHash,
+                V: Deserialize<'de>,
+                M: HashMixer,
+            {
+                type Value = ();
+
+                fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                #[cfg_attr(feature = "inline-more", inline)]
+                fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: MapAccess<'de>,
+                {
+                    self.0.clear();
+                    self.0.reserve(size_hint::cautious(map.size_hint()));
+
+                    while let Some((key, value)) = map.next_entry()? {
+                        self.0.insert(key, value);
+                    }
+
+                    Ok(())
+                }
+            }
+
+            deserializer.deserialize_map(MapInPlaceVisitor(place))
+        }
     }
 }
 
@@ -88,13 +131,15 @@ mod set {
     use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
     use serde::ser::{Serialize, Serializer};
 
+    use crate::mix::HashMixer;
     use crate::{AutoHash, AutoHashSet};
 
     use super::size_hint;
 
-    impl<T> Serialize for AutoHashSet<T>
+    impl<T, M> Serialize for AutoHashSet<T, M>
     where
         T: Serialize + Eq + AutoHash,
+        M: HashMixer,
     {
         #[cfg_attr(feature = "inline-more", inline)]
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -105,23 +150,25 @@ mod set {
         }
     }
 
-    impl<'de, T> Deserialize<'de> for AutoHashSet<T>
+    impl<'de, T, M> Deserialize<'de> for AutoHashSet<T, M>
     where
         T: Deserialize<'de> + Eq + AutoHash,
+        M: HashMixer + Default,
     {
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
             D: Deserializer<'de>,
         {
-            struct SeqVisitor<T> {
-                marker: PhantomData<AutoHashSet<T>>,
+            struct SeqVisitor<T, M> {
+                marker: PhantomData<AutoHashSet<T, M>>,
             }
 
-            impl<'de, T> Visitor<'de> for SeqVisitor<T>
+            impl<'de, T, M> Visitor<'de> for SeqVisitor<T, M>
             where
                 T: Deserialize<'de> + Eq + AutoHash,
+                M: HashMixer + Default,
             {
-                type Value = AutoHashSet<T>;
+                type Value = AutoHashSet<T, M>;
 
                 fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
                     formatter.write_str("a sequence")
@@ -132,8 +179,10 @@ mod set {
                 where
                     A: SeqAccess<'de>,
                 {
-                    let mut values =
-                        AutoHashSet::with_capacity(size_hint::cautious(seq.size_hint()));
+                    let mut values = AutoHashSet::with_capacity_and_mixer(
+                        size_hint::cautious(seq.size_hint()),
+                        M::default(),
+                    );
 
                     while let Some(value) = seq.next_element()? {
                         values.insert(value);
@@ -153,11 +202,12 @@ mod set {
         where
             D: Deserializer<'de>,
         {
-            struct SeqInPlaceVisitor<'a, T>(&'a mut AutoHashSet<T>);
+            struct SeqInPlaceVisitor<'a, T, M>(&'a mut AutoHashSet<T, M>);
 
-            impl<'a, 'de, T> Visitor<'de> for SeqInPlaceVisitor<'a, T>
+            impl<'a, 'de, T, M> Visitor<'de> for SeqInPlaceVisitor<'a, T, M>
             where
                 T: Deserialize<'de> + Eq + AutoHash,
+                M: HashMixer,
             {
                 type Value = ();
 
@@ -192,6 +242,7 @@ mod wrappers {
     use serde::ser::{Serialize, Serializer};
 
     use crate::wrappers::*;
+    use crate::AutoHash;
 
     impl Serialize for U64Hash {
         #[inline]
@@ -213,6 +264,26 @@ mod wrappers {
         }
     }
 
+    impl Serialize for U128Hash {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.0.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for U128Hash {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            u128::deserialize(deserializer).map(U128Hash)
+        }
+    }
+
     impl<T, H> Serialize for AutoHashed<T, H>
     where
         T: Serialize,
@@ -265,4 +336,89 @@ mod wrappers {
             T::deserialize(deserializer).map(Self::from)
         }
     }
+
+    impl<T, H> Serialize for LazyHashed<T, H>
+    where
+        T: Serialize,
+    {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.value.serialize(serializer)
+        }
+    }
+
+    impl<'de, T, H> Deserialize<'de> for LazyHashed<T, H>
+    where
+        T: Deserialize<'de>,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            T::deserialize(deserializer).map(Self::from)
+        }
+    }
+
+    #[cfg(target_has_atomic = "64")]
+    impl<T, H> Serialize for SyncLazyHashed<T, H>
+    where
+        T: Serialize,
+    {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.value.serialize(serializer)
+        }
+    }
+
+    #[cfg(target_has_atomic = "64")]
+    impl<'de, T, H> Deserialize<'de> for SyncLazyHashed<T, H>
+    where
+        T: Deserialize<'de>,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            T::deserialize(deserializer).map(Self::from)
+        }
+    }
+
+    /// Serializes as a `(hash, value)` tuple.
+    impl<T> Serialize for RawHashed<T>
+    where
+        T: Serialize,
+    {
+        #[inline]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            (self.get_hash(), &self.value).serialize(serializer)
+        }
+    }
+
+    /// Deserializes from a `(hash, value)` tuple, trusting the hash.
+    ///
+    /// See `autohash::serde::checked` to verify the hash instead.
+    impl<'de, T> Deserialize<'de> for RawHashed<T>
+    where
+        T: Deserialize<'de>,
+    {
+        #[inline]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let (hash, value) = <(u64, T)>::deserialize(deserializer)?;
+            Ok(RawHashed::new(hash, value))
+        }
+    }
 }
@@ -0,0 +1,472 @@
+//! A map that detects hash flooding and falls back to an ordered structure.
+//!
+//! A seeded mixer like [`KeyedMix`] protects against keys whose hashes merely
+//! collide in their bucket bits, but nothing can separate keys whose self-hash
+//! is *identical*, like many `RawHashed` values with the same attacker-chosen
+//! hash. Those all share one probe sequence, so every operation on them
+//! degrades to a linear scan.
+//!
+//! [`FloodGuardMap`] counts key comparisons while inserting and finding keys.
+//! When a single insert or lookup compares more than a threshold of keys, the
+//! entries with that exact hash are moved to a `BTreeMap`, and all further
+//! operations for that hash use the `Ord` implementation of the keys instead.
+//! This keeps worst-case operations logarithmic, while the common case is just
+//! an `AutoHashMap` plus a check whether any hash has flooded at all.
+//!
+//! Lookups through `&self` can't move any entries, so they only note that a
+//! flood was seen, and the next call through `&mut self` moves the flooded
+//! entries before it does anything else.
+//!
+//! [`KeyedMix`]: ../mix/struct.KeyedMix.html
+//! [`FloodGuardMap`]: struct.FloodGuardMap.html
+
+use crate::map::{self, make_hash, make_hasher, AutoHashMap};
+use crate::mix::{HashMixer, NoMix};
+use crate::AutoHash;
+use alloc::collections::{btree_map, BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt::{self, Debug};
+use core::iter::{Chain, FromIterator, FusedIterator};
+use core::mem;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// The default number of key comparisons in one insert or lookup that will be
+/// treated as a flood of the hash being inserted or found.
+///
+/// A table with well-distributed hashes will only compare keys with the same
+/// 7-bit control tag in a short probe sequence, so this is very unlikely to be
+/// reached by accident.
+pub const DEFAULT_THRESHOLD: usize = 16;
+
+/// A hash map that moves flooded hashes into an ordered `BTreeMap`.
+///
+/// See the [module documentation] for details.
+///
+/// Keys must implement `Ord` in a way that is consistent with their `Eq`, and
+/// lookups use `Borrow` for both the hashed and ordered parts of the map.
+///
+/// [module documentation]: index.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::flood::FloodGuardMap;
+/// use autohash::AutoHash;
+///
+/// #[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// struct Flood(u32);
+///
+/// impl AutoHash for Flood {
+///     fn get_hash(&self) -> u64 {
+///         0 // Every key has the same hash!
+///     }
+/// }
+///
+/// let mut map = FloodGuardMap::new();
+/// for i in 0..1000 {
+///     map.insert(Flood(i), i);
+/// }
+///
+/// assert_eq!(map.len(), 1000);
+/// assert_eq!(map.flooded_hashes(), 1);
+/// assert!(map.overflow_len() > 900);
+/// assert_eq!(map.get(&Flood(500)), Some(&500));
+/// ```
+pub struct FloodGuardMap<K, V, M = NoMix> {
+    map: AutoHashMap<K, V, M>,
+    overflow: BTreeMap<K, V>,
+    flooded: BTreeSet<u64>,
+    threshold: usize,
+    /// Set by a shared lookup that compared too many keys.
+    pending: AtomicBool,
+}
+
+impl<K, V> FloodGuardMap<K, V, NoMix> {
+    /// Creates an empty `FloodGuardMap` with the [`DEFAULT_THRESHOLD`].
+    ///
+    /// [`DEFAULT_THRESHOLD`]: constant.DEFAULT_THRESHOLD.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_mixer(NoMix)
+    }
+}
+
+impl<K, V, M> FloodGuardMap<K, V, M> {
+    /// Creates an empty `FloodGuardMap` which will use the given mixer to
+    /// post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self::with_threshold_and_mixer(DEFAULT_THRESHOLD, mixer)
+    }
+
+    /// Creates an empty `FloodGuardMap` which will move a hash to the overflow
+    /// map after comparing more than `threshold` keys in a single insert or
+    /// lookup.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is 0.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_threshold_and_mixer(threshold: usize, mixer: M) -> Self {
+        assert!(threshold > 0, "the flood threshold must be non-zero");
+        Self {
+            map: AutoHashMap::with_mixer(mixer),
+            overflow: BTreeMap::new(),
+            flooded: BTreeSet::new(),
+            threshold,
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the number of key comparisons that will trigger a flood.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns a reference to the map's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        self.map.mixer()
+    }
+
+    /// Returns the number of elements in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.map.len() + self.overflow.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements that have been moved to the ordered
+    /// overflow map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn overflow_len(&self) -> usize {
+        self.overflow.len()
+    }
+
+    /// Returns the number of distinct hashes that have flooded.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn flooded_hashes(&self) -> usize {
+        self.flooded.len()
+    }
+
+    /// An iterator visiting all key-value pairs, first in arbitrary order from
+    /// the hashed map, then in key order from the overflow map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.map.iter().chain(self.overflow.iter()),
+        }
+    }
+
+    /// Clears the map, removing all key-value pairs, and forgetting all
+    /// flooded hashes.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.overflow.clear();
+        self.flooded.clear();
+        *self.pending.get_mut() = false;
+    }
+}
+
+impl<K, V, M> FloodGuardMap<K, V, M>
+where
+    K: Eq + AutoHash + Ord,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn is_flooded(&self, hash: u64) -> bool {
+        !self.flooded.is_empty() && self.flooded.contains(&hash)
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    #[inline]
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: AutoHash + Ord,
+    {
+        let hash = make_hash(self.mixer(), k);
+        if self.is_flooded(hash) {
+            return self.overflow.get(k);
+        }
+        let mut compared = 0;
+        let found = self.map.table.get(hash, |x| {
+            compared += 1;
+            k == x.0.borrow()
+        });
+        if compared > self.threshold {
+            // Leave the flooded entries for the next `&mut self` call to move.
+            self.pending.store(true, Ordering::Relaxed);
+        }
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match found {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    #[inline]
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: AutoHash + Ord,
+    {
+        self.settle();
+        let hash = make_hash(self.mixer(), k);
+        if self.is_flooded(hash) {
+            return self.overflow.get_mut(k);
+        }
+        let mut compared = 0;
+        let found = self.map.table.find(hash, |x| {
+            compared += 1;
+            k == x.0.borrow()
+        });
+        if compared > self.threshold {
+            self.flood(hash);
+            return self.overflow.get_mut(k);
+        }
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match found {
+            Some(bucket) => Some(unsafe { &mut bucket.as_mut().1 }),
+            None => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: AutoHash + Ord,
+    {
+        self.get(k).is_some()
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did have this key present, the value is updated, and the
+    /// old value is returned. The key is not updated.
+    ///
+    /// If this insert compares more than the threshold of keys, then all
+    /// entries with the same hash are moved to the overflow map.
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.settle();
+        let hash = make_hash(self.mixer(), &k);
+        if self.is_flooded(hash) {
+            return match self.overflow.entry(k) {
+                btree_map::Entry::Occupied(mut entry) => Some(entry.insert(v)),
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(v);
+                    None
+                }
+            };
+        }
+
+        let mut compared = 0;
+        let found = self.map.table.get_mut(hash, |x| {
+            compared += 1;
+            k == x.0
+        });
+        if let Some((_, item)) = found {
+            return Some(mem::replace(item, v));
+        }
+
+        if compared > self.threshold {
+            self.flood(hash);
+            self.overflow.insert(k, v);
+        } else {
+            let hasher = make_hasher(&self.map.mixer);
+            self.map.table.insert(hash, (k, v), hasher);
+        }
+        None
+    }
+
+    /// Moves the entries for any flood that a shared lookup has seen.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn settle(&mut self) {
+        if mem::replace(self.pending.get_mut(), false) {
+            self.rescan();
+        }
+    }
+
+    /// Finds each key again, counting comparisons like an insert would, and
+    /// floods every hash whose keys took more than the threshold to find.
+    #[cold]
+    fn rescan(&mut self) {
+        let mut hashes = BTreeSet::new();
+        let mixer = &self.map.mixer;
+        let table = &self.map.table;
+        unsafe {
+            for bucket in table.iter() {
+                let key = &bucket.as_ref().0;
+                let hash = make_hash(mixer, key);
+                let mut compared = 0;
+                table.get(hash, |x| {
+                    compared += 1;
+                    *key == x.0
+                });
+                if compared > self.threshold {
+                    hashes.insert(hash);
+                }
+            }
+        }
+        for hash in hashes {
+            self.flood(hash);
+        }
+    }
+
+    /// Moves all entries with exactly this hash into the overflow map.
+    #[cold]
+    fn flood(&mut self, hash: u64) {
+        let mixer = &self.map.mixer;
+        let table = &mut self.map.table;
+        unsafe {
+            // Other keys may share the control tag, so check the full hash.
+            let buckets: Vec<_> = table
+                .iter_hash(hash)
+                .filter(|bucket| make_hash(mixer, &bucket.as_ref().0) == hash)
+                .collect();
+            for bucket in buckets {
+                let (k, v) = table.remove(bucket);
+                self.overflow.insert(k, v);
+            }
+        }
+        self.flooded.insert(hash);
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: AutoHash + Ord,
+    {
+        self.settle();
+        let hash = make_hash(self.mixer(), k);
+        if self.is_flooded(hash) {
+            return self.overflow.remove(k);
+        }
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.table.remove_entry(hash, |x| k == x.0.borrow()) {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+}
+
+impl<K: Clone, V: Clone, M: Clone> Clone for FloodGuardMap<K, V, M> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            overflow: self.overflow.clone(),
+            flooded: self.flooded.clone(),
+            threshold: self.threshold,
+            pending: AtomicBool::new(self.pending.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, M> Debug for FloodGuardMap<K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, M: Default> Default for FloodGuardMap<K, V, M> {
+    /// Creates an empty `FloodGuardMap<K, V, M>`, with the `Default` value for the mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::with_mixer(M::default())
+    }
+}
+
+impl<K, V, M> Extend<(K, V)> for FloodGuardMap<K, V, M>
+where
+    K: Eq + AutoHash + Ord,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(move |(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<K, V, M> FromIterator<(K, V)> for FloodGuardMap<K, V, M>
+where
+    K: Eq + AutoHash + Ord,
+    M: HashMixer + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a FloodGuardMap<K, V, M> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of a `FloodGuardMap`.
+///
+/// This `struct` is created by the [`iter`] method on [`FloodGuardMap`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.FloodGuardMap.html#method.iter
+/// [`FloodGuardMap`]: struct.FloodGuardMap.html
+pub struct Iter<'a, K, V> {
+    inner: Chain<map::Iter<'a, K, V>, btree_map::Iter<'a, K, V>>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.inner.next()
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
@@ -0,0 +1,1546 @@
+//! A hash map that iterates in insertion order.
+
+use crate::mix::{HashMixer, NoMix};
+use crate::{AutoHash, Equivalent};
+use alloc::vec::{self, Vec};
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::iter::{FromIterator, FusedIterator};
+use core::mem;
+use core::ops::Index;
+use core::slice;
+use hashbrown::raw::RawTable;
+
+/// A hash map that keeps its entries in insertion order.
+///
+/// The entries are stored densely in a `Vec`, and a [`RawTable`] of indices
+/// into that `Vec` is keyed by each key's mixed [`AutoHash`] value, which is
+/// saved with the entry. Lookups by key take the same time as in an
+/// [`AutoHashMap`], and every entry can also be reached by its position with
+/// [`get_index`].
+///
+/// Removing an entry either swaps the last entry into its place with
+/// [`swap_remove`], or shifts every later entry down by one with
+/// [`shift_remove`], which keeps the order but takes linear time.
+///
+/// [`RawTable`]: https://docs.rs/hashbrown/0.9/hashbrown/raw/struct.RawTable.html
+/// [`AutoHash`]: ../../trait.AutoHash.html
+/// [`AutoHashMap`]: ../../map/struct.AutoHashMap.html
+/// [`get_index`]: #method.get_index
+/// [`swap_remove`]: #method.swap_remove
+/// [`shift_remove`]: #method.shift_remove
+///
+/// # Examples
+///
+/// ```
+/// use autohash::index::AutoIndexMap;
+///
+/// let mut map = AutoIndexMap::new();
+/// map.insert(30, "c");
+/// map.insert(10, "a");
+/// map.insert(20, "b");
+///
+/// let keys: Vec<_> = map.keys().copied().collect();
+/// assert_eq!(keys, [30, 10, 20]);
+/// assert_eq!(map.get_index(1), Some((&10, &"a")));
+///
+/// map.sort_keys();
+/// assert_eq!(map.get_index_of(&30), Some(2));
+/// ```
+pub struct AutoIndexMap<K, V, M = NoMix> {
+    mixer: M,
+    indices: RawTable<usize>,
+    entries: Vec<Bucket<K, V>>,
+}
+
+#[derive(Clone)]
+struct Bucket<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+impl<K, V> Bucket<K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn refs(&self) -> (&K, &V) {
+        (&self.key, &self.value)
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn ref_mut(&mut self) -> (&K, &mut V) {
+        (&self.key, &mut self.value)
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn key_value(self) -> (K, V) {
+        (self.key, self.value)
+    }
+}
+
+impl<K: Clone, V: Clone, M: Clone> Clone for AutoIndexMap<K, V, M> {
+    fn clone(&self) -> Self {
+        AutoIndexMap {
+            mixer: self.mixer.clone(),
+            indices: self.indices.clone(),
+            entries: self.entries.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.indices.clone_from(&source.indices);
+        self.entries.clone_from(&source.entries);
+
+        // Update mixer after the entries, in case their clone panics.
+        self.mixer.clone_from(&source.mixer);
+    }
+}
+
+/// Rehashes an index by reading the hash stored in its entry.
+#[cfg_attr(feature = "inline-more", inline)]
+fn stored_hash<K, V>(entries: &[Bucket<K, V>]) -> impl Fn(&usize) -> u64 + '_ {
+    move |&i| entries[i].hash
+}
+
+/// Compares the stored hash before calling `Equivalent` on the keys.
+#[cfg_attr(feature = "inline-more", inline)]
+fn equivalent_key<'a, Q, K, V>(
+    hash: u64,
+    k: &'a Q,
+    entries: &'a [Bucket<K, V>],
+) -> impl Fn(&usize) -> bool + 'a
+where
+    Q: ?Sized + Equivalent<K>,
+{
+    move |&i| {
+        let entry = &entries[i];
+        hash == entry.hash && k.equivalent(&entry.key)
+    }
+}
+
+/// Finds the index that refers to a particular position.
+#[cfg_attr(feature = "inline-more", inline)]
+fn equivalent_index(index: usize) -> impl Fn(&usize) -> bool {
+    move |&i| i == index
+}
+
+/// Finishes a `retain`, even if its predicate panics.
+///
+/// The entries before `kept` are kept, and the ones from `kept` to `next` are
+/// removed. Any entries from `next` on weren't visited yet, so they are kept.
+struct RebuildGuard<'a, K, V, M> {
+    map: &'a mut AutoIndexMap<K, V, M>,
+    kept: usize,
+    next: usize,
+}
+
+impl<K, V, M> Drop for RebuildGuard<'_, K, V, M> {
+    fn drop(&mut self) {
+        if self.kept < self.next {
+            let entries = &mut self.map.entries;
+            if self.next == entries.len() {
+                entries.truncate(self.kept);
+            } else {
+                entries.drain(self.kept..self.next);
+            }
+            self.map.rebuild_indices();
+        }
+    }
+}
+
+impl<K, V> AutoIndexMap<K, V, NoMix> {
+    /// Creates an empty `AutoIndexMap`.
+    ///
+    /// The map is initially created with a capacity of 0, so it will not
+    /// allocate until it is first inserted into.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_mixer(NoMix)
+    }
+
+    /// Creates an empty `AutoIndexMap` with the specified capacity.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_mixer(capacity, NoMix)
+    }
+}
+
+impl<K, V, M> AutoIndexMap<K, V, M> {
+    /// Creates an empty `AutoIndexMap` which will use the given mixer to
+    /// post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self {
+            mixer,
+            indices: RawTable::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates an empty `AutoIndexMap` with the specified capacity, which
+    /// will use the given mixer to post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_and_mixer(capacity: usize, mixer: M) -> Self {
+        Self {
+            mixer,
+            indices: RawTable::with_capacity(capacity),
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a reference to the map's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        &self.mixer
+    }
+
+    /// Returns the number of elements the map can hold without reallocating.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn capacity(&self) -> usize {
+        usize::min(self.indices.capacity(), self.entries.capacity())
+    }
+
+    /// An iterator visiting all keys in insertion order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all values in insertion order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all values mutably in insertion order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in insertion order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in insertion order, with
+    /// mutable references to the values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+
+    /// Returns the number of elements in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Clears the map, returning all key-value pairs in insertion order as an
+    /// iterator. Keeps the allocated memory for reuse.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        self.indices.clear_no_drop();
+        Drain {
+            inner: self.entries.drain(..),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate, keeping the
+    /// order of the remaining elements.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k,&mut v)` returns `false`.
+    ///
+    /// If `f` panics, the entries it has already removed stay removed.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let len = self.entries.len();
+        let mut guard = RebuildGuard {
+            map: self,
+            kept: 0,
+            next: 0,
+        };
+        while guard.next < len {
+            let entry = &mut guard.map.entries[guard.next];
+            if f(&entry.key, &mut entry.value) {
+                guard.map.entries.swap(guard.kept, guard.next);
+                guard.kept += 1;
+            }
+            guard.next += 1;
+        }
+    }
+
+    /// Clears the map, removing all key-value pairs. Keeps the allocated memory
+    /// for reuse.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn clear(&mut self) {
+        self.indices.clear_no_drop();
+        self.entries.clear();
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the `AutoIndexMap`. The collection may reserve more space to avoid
+    /// frequent reallocations.
+    ///
+    /// This does not need to hash any of the keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// [`usize`]: https://doc.rust-lang.org/std/primitive.usize.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn reserve(&mut self, additional: usize) {
+        self.indices.reserve(additional, stored_hash(&self.entries));
+        self.entries.reserve(additional);
+    }
+
+    /// Shrinks the capacity of the map as much as possible. It will drop
+    /// down as much as possible while maintaining the internal rules
+    /// and possibly leaving some space in accordance with the resize policy.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shrink_to_fit(&mut self) {
+        self.indices.shrink_to(0, stored_hash(&self.entries));
+        self.entries.shrink_to_fit();
+    }
+
+    /// Returns the key-value pair at the given position in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::index::AutoIndexMap;
+    ///
+    /// let map: AutoIndexMap<_, _> = vec![(5, 'x'), (1, 'y')].into_iter().collect();
+    /// assert_eq!(map.get_index(0), Some((&5, &'x')));
+    /// assert_eq!(map.get_index(2), None);
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.entries.get(index) {
+            Some(entry) => Some(entry.refs()),
+            None => None,
+        }
+    }
+
+    /// Returns the key and a mutable reference to the value at the given
+    /// position in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.entries.get_mut(index) {
+            Some(entry) => Some(entry.ref_mut()),
+            None => None,
+        }
+    }
+
+    /// Removes the key-value pair at the given position, replacing it with
+    /// the last pair in the map.
+    ///
+    /// This takes constant time, but perturbs the order of the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        let hash = match self.entries.get(index) {
+            Some(entry) => entry.hash,
+            None => return None,
+        };
+        self.indices.erase_entry(hash, equivalent_index(index));
+        Some(self.swap_remove_finish(index))
+    }
+
+    /// Removes the key-value pair at the given position, shifting all of the
+    /// later pairs down by one.
+    ///
+    /// This keeps the order of the map, but takes linear time.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        let hash = match self.entries.get(index) {
+            Some(entry) => entry.hash,
+            None => return None,
+        };
+        self.indices.erase_entry(hash, equivalent_index(index));
+        Some(self.shift_remove_finish(index))
+    }
+
+    /// Removes the last key-value pair in the map and returns it.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        match self.entries.pop() {
+            Some(entry) => {
+                let index = self.entries.len();
+                self.indices
+                    .erase_entry(entry.hash, equivalent_index(index));
+                Some(entry.key_value())
+            }
+            None => None,
+        }
+    }
+
+    /// Sorts the map's key-value pairs with the given comparison function.
+    ///
+    /// The sort is stable, and the positions of every entry are updated
+    /// without hashing any of the keys.
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        self.entries
+            .sort_by(move |a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        self.rebuild_indices();
+    }
+
+    /// Sorts the map's key-value pairs by their keys.
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        self.sort_by(|k1, _, k2, _| Ord::cmp(k1, k2));
+    }
+
+    /// Moves the last entry into `index`, after its index was erased.
+    fn swap_remove_finish(&mut self, index: usize) -> (K, V) {
+        let entry = self.entries.swap_remove(index);
+        if let Some(moved) = self.entries.get(index) {
+            let last = self.entries.len();
+            let slot = self.indices.get_mut(moved.hash, equivalent_index(last));
+            *slot.expect("index not found for moved entry") = index;
+        }
+        entry.key_value()
+    }
+
+    /// Shifts the entries after `index` down by one, after its index was erased.
+    fn shift_remove_finish(&mut self, index: usize) -> (K, V) {
+        let entry = self.entries.remove(index);
+        for (i, moved) in self.entries.iter().enumerate().skip(index) {
+            let slot = self.indices.get_mut(moved.hash, equivalent_index(i + 1));
+            *slot.expect("index not found for moved entry") = i;
+        }
+        entry.key_value()
+    }
+
+    /// Refills the indices from the stored hashes, after the entries moved.
+    ///
+    /// There are never more entries than there were indices, so this reuses
+    /// the table's allocation.
+    fn rebuild_indices(&mut self) {
+        self.indices.clear_no_drop();
+        for (i, entry) in self.entries.iter().enumerate() {
+            self.indices.insert_no_grow(entry.hash, i);
+        }
+    }
+
+    /// Appends a new entry, which is not already in the map.
+    fn push(&mut self, hash: u64, key: K, value: V) -> usize {
+        let index = self.entries.len();
+        self.indices.insert(hash, index, stored_hash(&self.entries));
+        if index == self.entries.capacity() {
+            // Keep the entries' capacity in step with the indices.
+            self.entries.reserve_exact(self.indices.capacity() - index);
+        }
+        self.entries.push(Bucket { hash, key, value });
+        index
+    }
+}
+
+impl<K, V, M> AutoIndexMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn hash<Q: AutoHash + ?Sized>(&self, k: &Q) -> u64 {
+        self.mixer.mix(k.get_hash())
+    }
+
+    /// Returns the position of the key in the map, if it is present.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../../trait.AutoHash.html
+    /// [`Equivalent`]: ../../trait.Equivalent.html
+    #[inline]
+    pub fn get_index_of<Q: ?Sized>(&self, k: &Q) -> Option<usize>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.hash(k);
+        self.indices
+            .get(hash, equivalent_key(hash, k, &self.entries))
+            .copied()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    #[inline]
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_index_of(k) {
+            Some(i) => Some(&self.entries[i].value),
+            None => None,
+        }
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    #[inline]
+    pub fn get_key_value<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_index_of(k) {
+            Some(i) => Some(self.entries[i].refs()),
+            None => None,
+        }
+    }
+
+    /// Returns the position and key-value pair corresponding to the supplied
+    /// key.
+    #[inline]
+    pub fn get_full<Q: ?Sized>(&self, k: &Q) -> Option<(usize, &K, &V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_index_of(k) {
+            Some(i) => {
+                let entry = &self.entries[i];
+                Some((i, &entry.key, &entry.value))
+            }
+            None => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        self.get_index_of(k).is_some()
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_index_of(k) {
+            Some(i) => Some(&mut self.entries[i].value),
+            None => None,
+        }
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, the pair is appended at the
+    /// end, and [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated in place,
+    /// and the old value is returned. The key and its position are not
+    /// updated, though.
+    ///
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        self.insert_full(k, v).1
+    }
+
+    /// Inserts a key-value pair into the map, and returns its position along
+    /// with the old value, if there was one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::index::AutoIndexMap;
+    ///
+    /// let mut map = AutoIndexMap::new();
+    /// assert_eq!(map.insert_full('a', 1), (0, None));
+    /// assert_eq!(map.insert_full('b', 2), (1, None));
+    /// assert_eq!(map.insert_full('a', 3), (0, Some(1)));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert_full(&mut self, k: K, v: V) -> (usize, Option<V>) {
+        match self.entry(k) {
+            Entry::Occupied(mut entry) => (entry.index(), Some(entry.insert(v))),
+            Entry::Vacant(entry) => {
+                let index = entry.index();
+                entry.insert(v);
+                (index, None)
+            }
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place
+    /// manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::index::AutoIndexMap;
+    ///
+    /// let mut letters = AutoIndexMap::new();
+    /// for ch in "a short treatise on fungi".chars() {
+    ///     *letters.entry(ch).or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!(letters[&'s'], 2);
+    /// assert_eq!(letters.get_index(0), Some((&'a', &2)));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, M> {
+        let hash = self.hash(&key);
+        match self
+            .indices
+            .get(hash, equivalent_key(hash, &key, &self.entries))
+        {
+            Some(&index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry {
+                map: self,
+                hash,
+                key,
+            }),
+        }
+    }
+
+    /// Removes a key from the map by swapping the last entry into its place,
+    /// returning the value at the key if the key was previously in the map.
+    ///
+    /// This takes constant time, but perturbs the order of the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn swap_remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.swap_remove_full(k) {
+            Some((_, _, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Removes a key from the map by swapping the last entry into its place,
+    /// returning the position, key, and value if the key was previously in
+    /// the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn swap_remove_full<Q: ?Sized>(&mut self, k: &Q) -> Option<(usize, K, V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.hash(k);
+        match self
+            .indices
+            .remove_entry(hash, equivalent_key(hash, k, &self.entries))
+        {
+            Some(index) => {
+                let (key, value) = self.swap_remove_finish(index);
+                Some((index, key, value))
+            }
+            None => None,
+        }
+    }
+
+    /// Removes a key from the map by shifting all later entries down by one,
+    /// returning the value at the key if the key was previously in the map.
+    ///
+    /// This keeps the order of the map, but takes linear time.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.shift_remove_full(k) {
+            Some((_, _, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Removes a key from the map by shifting all later entries down by one,
+    /// returning the position, key, and value if the key was previously in
+    /// the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_remove_full<Q: ?Sized>(&mut self, k: &Q) -> Option<(usize, K, V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.hash(k);
+        match self
+            .indices
+            .remove_entry(hash, equivalent_key(hash, k, &self.entries))
+        {
+            Some(index) => {
+                let (key, value) = self.shift_remove_finish(index);
+                Some((index, key, value))
+            }
+            None => None,
+        }
+    }
+}
+
+impl<K, V, M> PartialEq for AutoIndexMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    V: PartialEq,
+    M: HashMixer,
+{
+    /// Compares the maps' entries, regardless of their order.
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter()
+            .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K, V, M> Eq for AutoIndexMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    V: Eq,
+    M: HashMixer,
+{
+}
+
+impl<K, V, M> Debug for AutoIndexMap<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, M: Default> Default for AutoIndexMap<K, V, M> {
+    /// Creates an empty `AutoIndexMap<K, V, M>`, with the `Default` value for the mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::with_mixer(M::default())
+    }
+}
+
+impl<K, Q: ?Sized, V, M> Index<&Q> for AutoIndexMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    Q: AutoHash + Equivalent<K>,
+    M: HashMixer,
+{
+    type Output = V;
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the `AutoIndexMap`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V, M> FromIterator<(K, V)> for AutoIndexMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let iter = iter.into_iter();
+        let mut map = Self::with_capacity_and_mixer(iter.size_hint().0, M::default());
+        iter.for_each(|(k, v)| {
+            map.insert(k, v);
+        });
+        map
+    }
+}
+
+/// Appends all new key-values from the iterator and replaces values with existing
+/// keys with new values returned from the iterator, keeping their positions.
+impl<K, V, M> Extend<(K, V)> for AutoIndexMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        // Keys may be already present or show multiple times in the iterator.
+        // Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map
+        // will only resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            (iter.size_hint().0 + 1) / 2
+        };
+        self.reserve(reserve);
+        iter.for_each(move |(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<'a, K, V, M> Extend<(&'a K, &'a V)> for AutoIndexMap<K, V, M>
+where
+    K: Eq + AutoHash + Copy,
+    V: Copy,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a AutoIndexMap<K, V, M> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a mut AutoIndexMap<K, V, M> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, M> IntoIterator for AutoIndexMap<K, V, M> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Creates a consuming iterator, that is, one that moves each key-value
+    /// pair out of the map in insertion order. The map cannot be used after
+    /// calling this.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            inner: self.entries.into_iter(),
+        }
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the [`entry`] method on [`AutoIndexMap`].
+///
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+/// [`entry`]: struct.AutoIndexMap.html#method.entry
+pub enum Entry<'a, K, V, M> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, M>),
+
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, M>),
+}
+
+impl<K: Debug, V: Debug, M> Debug for Entry<'_, K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Entry::Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
+            Entry::Occupied(ref o) => f.debug_tuple("Entry").field(o).finish(),
+        }
+    }
+}
+
+/// A view into an occupied entry in an `AutoIndexMap`.
+/// It is part of the [`Entry`] enum.
+///
+/// [`Entry`]: enum.Entry.html
+pub struct OccupiedEntry<'a, K, V, M> {
+    map: &'a mut AutoIndexMap<K, V, M>,
+    index: usize,
+}
+
+impl<K: Debug, V: Debug, M> Debug for OccupiedEntry<'_, K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// A view into a vacant entry in an `AutoIndexMap`.
+/// It is part of the [`Entry`] enum.
+///
+/// [`Entry`]: enum.Entry.html
+pub struct VacantEntry<'a, K, V, M> {
+    map: &'a mut AutoIndexMap<K, V, M>,
+    hash: u64,
+    key: K,
+}
+
+impl<K: Debug, V, M> Debug for VacantEntry<'_, K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.key()).finish()
+    }
+}
+
+impl<'a, K, V, M> Entry<'a, K, V, M> {
+    /// Sets the value of the entry, and returns an OccupiedEntry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(self, value: V) -> OccupiedEntry<'a, K, V, M> {
+        match self {
+            Entry::Occupied(mut entry) => {
+                entry.insert(value);
+                entry
+            }
+            Entry::Vacant(entry) => entry.insert_entry(value),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default if empty, and returns
+    /// a mutable reference to the value in the entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function,
+    /// which takes the key as its argument, and returns a mutable reference to the value in the
+    /// entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Returns a reference to this entry's key.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn key(&self) -> &K {
+        match *self {
+            Entry::Occupied(ref entry) => entry.key(),
+            Entry::Vacant(ref entry) => entry.key(),
+        }
+    }
+
+    /// Returns the position of this entry, or where it would be inserted.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn index(&self) -> usize {
+        match *self {
+            Entry::Occupied(ref entry) => entry.index(),
+            Entry::Vacant(ref entry) => entry.index(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V: Default, M> Entry<'a, K, V, M> {
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn or_default(self) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Default::default()),
+        }
+    }
+}
+
+impl<'a, K, V, M> OccupiedEntry<'a, K, V, M> {
+    /// Gets a reference to the key in the entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn key(&self) -> &K {
+        &self.map.entries[self.index].key
+    }
+
+    /// Returns the position of the entry in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get(&self) -> &V {
+        &self.map.entries[self.index].value
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    ///
+    /// If you need a reference to the `OccupiedEntry` which may outlive the
+    /// destruction of the `Entry` value, see [`into_mut`].
+    ///
+    /// [`into_mut`]: #method.into_mut
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.entries[self.index].value
+    }
+
+    /// Converts the OccupiedEntry into a mutable reference to the value in the entry
+    /// with a lifetime bound to the map itself.
+    ///
+    /// If you need multiple references to the `OccupiedEntry`, see [`get_mut`].
+    ///
+    /// [`get_mut`]: #method.get_mut
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.entries[self.index].value
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value out of the entry by swapping the last entry into its
+    /// place, and returns it.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Takes the key and value out of the map by swapping the last entry into
+    /// their place.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove_entry(self) -> (K, V) {
+        let index = self.index;
+        self.map
+            .swap_remove_index(index)
+            .expect("entry index out of bounds")
+    }
+
+    /// Takes the value out of the entry by shifting all later entries down
+    /// by one, and returns it.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_remove(self) -> V {
+        self.shift_remove_entry().1
+    }
+
+    /// Takes the key and value out of the map by shifting all later entries
+    /// down by one.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_remove_entry(self) -> (K, V) {
+        let index = self.index;
+        self.map
+            .shift_remove_index(index)
+            .expect("entry index out of bounds")
+    }
+}
+
+impl<'a, K, V, M> VacantEntry<'a, K, V, M> {
+    /// Gets a reference to the key that would be used when inserting a value
+    /// through the `VacantEntry`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Returns the position the entry will have once it is inserted, which
+    /// is the end of the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn index(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Sets the value of the entry with the VacantEntry's key,
+    /// and returns a mutable reference to it.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = self.map.push(self.hash, self.key, value);
+        &mut self.map.entries[index].value
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn insert_entry(self, value: V) -> OccupiedEntry<'a, K, V, M> {
+        let index = self.map.push(self.hash, self.key, value);
+        OccupiedEntry {
+            map: self.map,
+            index,
+        }
+    }
+}
+
+/// An iterator over the entries of an `AutoIndexMap` in insertion order.
+///
+/// This `struct` is created by the [`iter`] method on [`AutoIndexMap`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.AutoIndexMap.html#method.iter
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Bucket<K, V>>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(entry) => Some(entry.refs()),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next_back() {
+            Some(entry) => Some(entry.refs()),
+            None => None,
+        }
+    }
+}
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// A mutable iterator over the entries of an `AutoIndexMap` in insertion order.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`AutoIndexMap`]. See its
+/// documentation for more.
+///
+/// [`iter_mut`]: struct.AutoIndexMap.html#method.iter_mut
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+pub struct IterMut<'a, K, V> {
+    inner: slice::IterMut<'a, Bucket<K, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(entry) => Some(entry.ref_mut()),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next_back() {
+            Some(entry) => Some(entry.ref_mut()),
+            None => None,
+        }
+    }
+}
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+impl<K, V> fmt::Debug for IterMut<'_, K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = self.inner.as_slice().iter().map(Bucket::refs);
+        f.debug_list().entries(iter).finish()
+    }
+}
+
+/// An owning iterator over the entries of an `AutoIndexMap` in insertion order.
+///
+/// This `struct` is created by the [`into_iter`] method on [`AutoIndexMap`]
+/// (provided by the `IntoIterator` trait). See its documentation for more.
+///
+/// [`into_iter`]: struct.AutoIndexMap.html#method.into_iter
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+pub struct IntoIter<K, V> {
+    inner: vec::IntoIter<Bucket<K, V>>,
+}
+
+impl<K, V> IntoIter<K, V> {
+    /// Returns a iterator of references over the remaining items.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub(super) fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.as_slice().iter(),
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(K, V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(entry) => Some(entry.key_value()),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next_back() {
+            Some(entry) => Some(entry.key_value()),
+            None => None,
+        }
+    }
+}
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+impl<K: Debug, V: Debug> fmt::Debug for IntoIter<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the keys of an `AutoIndexMap` in insertion order.
+///
+/// This `struct` is created by the [`keys`] method on [`AutoIndexMap`]. See its
+/// documentation for more.
+///
+/// [`keys`]: struct.AutoIndexMap.html#method.keys
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+pub struct Keys<'a, K, V> {
+    inner: slice::Iter<'a, Bucket<K, V>>,
+}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Keys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Debug, V> fmt::Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(entry) => Some(&entry.key),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next_back() {
+            Some(entry) => Some(&entry.key),
+            None => None,
+        }
+    }
+}
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of an `AutoIndexMap` in insertion order.
+///
+/// This `struct` is created by the [`values`] method on [`AutoIndexMap`]. See its
+/// documentation for more.
+///
+/// [`values`]: struct.AutoIndexMap.html#method.values
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+pub struct Values<'a, K, V> {
+    inner: slice::Iter<'a, Bucket<K, V>>,
+}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Values {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V: Debug> fmt::Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a V> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(entry) => Some(&entry.value),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next_back() {
+            Some(entry) => Some(&entry.value),
+            None => None,
+        }
+    }
+}
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// A mutable iterator over the values of an `AutoIndexMap` in insertion order.
+///
+/// This `struct` is created by the [`values_mut`] method on [`AutoIndexMap`]. See its
+/// documentation for more.
+///
+/// [`values_mut`]: struct.AutoIndexMap.html#method.values_mut
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+pub struct ValuesMut<'a, K, V> {
+    inner: slice::IterMut<'a, Bucket<K, V>>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a mut V> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(entry) => Some(&mut entry.value),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for ValuesMut<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next_back() {
+            Some(entry) => Some(&mut entry.value),
+            None => None,
+        }
+    }
+}
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> fmt::Debug for ValuesMut<'_, K, V>
+where
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = self.inner.as_slice().iter().map(|entry| &entry.value);
+        f.debug_list().entries(iter).finish()
+    }
+}
+
+/// A draining iterator over the entries of an `AutoIndexMap` in insertion order.
+///
+/// This `struct` is created by the [`drain`] method on [`AutoIndexMap`]. See its
+/// documentation for more.
+///
+/// [`drain`]: struct.AutoIndexMap.html#method.drain
+/// [`AutoIndexMap`]: struct.AutoIndexMap.html
+pub struct Drain<'a, K, V> {
+    inner: vec::Drain<'a, Bucket<K, V>>,
+}
+
+impl<K, V> Drain<'_, K, V> {
+    /// Returns a iterator of references over the remaining items.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub(super) fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.as_slice().iter(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Drain<'a, K, V> {
+    type Item = (K, V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(K, V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(entry) => Some(entry.key_value()),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> DoubleEndedIterator for Drain<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next_back() {
+            Some(entry) => Some(entry.key_value()),
+            None => None,
+        }
+    }
+}
+impl<K, V> ExactSizeIterator for Drain<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Drain<'_, K, V> {}
+
+impl<K, V> fmt::Debug for Drain<'_, K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,16 @@
+//! Maps and sets that remember the order their entries were inserted.
+//!
+//! [`AutoIndexMap`] and [`AutoIndexSet`] keep their entries in a dense `Vec`,
+//! in insertion order, beside a hash table of positions in that `Vec`. Each
+//! entry also saves its mixed `AutoHash` value, so the table is rebuilt after
+//! sorting or removal without calling `get_hash` again. Entries can be
+//! reached by their key or by their position.
+//!
+//! [`AutoIndexMap`]: map/struct.AutoIndexMap.html
+//! [`AutoIndexSet`]: set/struct.AutoIndexSet.html
+
+pub mod map;
+pub mod set;
+
+pub use self::map::AutoIndexMap;
+pub use self::set::AutoIndexSet;
@@ -0,0 +1,613 @@
+//! A hash set that iterates in insertion order.
+
+use super::map::{self, AutoIndexMap, Keys};
+use crate::mix::{HashMixer, NoMix};
+use crate::{AutoHash, Equivalent};
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::{FromIterator, FusedIterator};
+
+/// A hash set that keeps its values in insertion order.
+///
+/// This is implemented as an [`AutoIndexMap`] where the value is `()`, so
+/// values can also be reached by their position, and removed by either
+/// swapping or shifting the values after them.
+///
+/// [`AutoIndexMap`]: ../map/struct.AutoIndexMap.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::index::AutoIndexSet;
+///
+/// let mut set: AutoIndexSet<u32> = vec![3, 1, 4, 1, 5].into_iter().collect();
+/// assert_eq!(set.iter().copied().collect::<Vec<_>>(), [3, 1, 4, 5]);
+///
+/// set.shift_remove(&1);
+/// assert_eq!(set.get_index(1), Some(&4));
+/// ```
+pub struct AutoIndexSet<T, M = NoMix> {
+    map: AutoIndexMap<T, (), M>,
+}
+
+impl<T: Clone, M: Clone> Clone for AutoIndexSet<T, M> {
+    fn clone(&self) -> Self {
+        AutoIndexSet {
+            map: self.map.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.map.clone_from(&source.map);
+    }
+}
+
+impl<T> AutoIndexSet<T, NoMix> {
+    /// Creates an empty `AutoIndexSet`.
+    ///
+    /// The set is initially created with a capacity of 0, so it will not
+    /// allocate until it is first inserted into.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self {
+            map: AutoIndexMap::new(),
+        }
+    }
+
+    /// Creates an empty `AutoIndexSet` with the specified capacity.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: AutoIndexMap::with_capacity(capacity),
+        }
+    }
+}
+
+impl<T, M> AutoIndexSet<T, M> {
+    /// Creates an empty `AutoIndexSet` which will use the given mixer to
+    /// post-process the hashes of its values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self {
+            map: AutoIndexMap::with_mixer(mixer),
+        }
+    }
+
+    /// Creates an empty `AutoIndexSet` with the specified capacity, which
+    /// will use the given mixer to post-process the hashes of its values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_and_mixer(capacity: usize, mixer: M) -> Self {
+        Self {
+            map: AutoIndexMap::with_capacity_and_mixer(capacity, mixer),
+        }
+    }
+
+    /// Returns a reference to the set's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        self.map.mixer()
+    }
+
+    /// Returns the number of elements the set can hold without reallocating.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// An iterator visiting all elements in insertion order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.map.keys(),
+        }
+    }
+
+    /// Returns the number of elements in the set.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Clears the set, returning all elements in insertion order in an
+    /// iterator.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            iter: self.map.drain(),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate, keeping the
+    /// order of the remaining elements.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|k, _| f(k));
+    }
+
+    /// Clears the set, removing all values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the `AutoIndexSet`. The collection may reserve more space to avoid
+    /// frequent reallocations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows `usize`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
+
+    /// Shrinks the capacity of the set as much as possible. It will drop
+    /// down as much as possible while maintaining the internal rules
+    /// and possibly leaving some space in accordance with the resize policy.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit()
+    }
+
+    /// Returns the value at the given position in the set.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_index(&self, index: usize) -> Option<&T> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.get_index(index) {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    /// Removes the value at the given position, replacing it with the last
+    /// value in the set.
+    ///
+    /// This takes constant time, but perturbs the order of the set.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<T> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.swap_remove_index(index) {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    /// Removes the value at the given position, shifting all of the later
+    /// values down by one.
+    ///
+    /// This keeps the order of the set, but takes linear time.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<T> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.shift_remove_index(index) {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    /// Removes the last value in the set and returns it.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn pop(&mut self) -> Option<T> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.pop() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    /// Sorts the set's values with the given comparison function.
+    ///
+    /// The sort is stable, and does not hash any of the values.
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.map.sort_by(move |a, _, b, _| cmp(a, b));
+    }
+
+    /// Sorts the set's values in their natural order.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.map.sort_keys();
+    }
+}
+
+impl<T, M> AutoIndexSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+    /// Returns `true` if the set contains a value.
+    ///
+    /// The value may be any type that is [`Equivalent`] to the set's value
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the value type.
+    ///
+    /// [`AutoHash`]: ../../trait.AutoHash.html
+    /// [`Equivalent`]: ../../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the value in the set, if any, that is equal to the given value.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.get_key_value(value) {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    /// Returns the position and value in the set, if any, that is equal to
+    /// the given value.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_full<Q: ?Sized>(&self, value: &Q) -> Option<(usize, &T)>
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.get_full(value) {
+            Some((i, k, _)) => Some((i, k)),
+            None => None,
+        }
+    }
+
+    /// Returns the position of the value in the set, if it is present.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_index_of<Q: ?Sized>(&self, value: &Q) -> Option<usize>
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        self.map.get_index_of(value)
+    }
+
+    /// Adds a value to the end of the set.
+    ///
+    /// If the set did not have this value present, `true` is returned.
+    ///
+    /// If the set did have this value present, `false` is returned, and its
+    /// position is not changed.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Adds a value to the end of the set, if it was not already present,
+    /// and returns its position along with whether it was newly inserted.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert_full(&mut self, value: T) -> (usize, bool) {
+        let (index, old) = self.map.insert_full(value, ());
+        (index, old.is_none())
+    }
+
+    /// Removes a value from the set by swapping the last value into its
+    /// place. Returns whether the value was present in the set.
+    ///
+    /// This takes constant time, but perturbs the order of the set.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn swap_remove<Q: ?Sized>(&mut self, value: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        self.map.swap_remove(value).is_some()
+    }
+
+    /// Removes a value from the set by shifting all later values down by
+    /// one. Returns whether the value was present in the set.
+    ///
+    /// This keeps the order of the set, but takes linear time.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_remove<Q: ?Sized>(&mut self, value: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        self.map.shift_remove(value).is_some()
+    }
+
+    /// Removes and returns the value in the set, if any, that is equal to the
+    /// given one, by swapping the last value into its place.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn swap_take<Q: ?Sized>(&mut self, value: &Q) -> Option<T>
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.swap_remove_full(value) {
+            Some((_, k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    /// Removes and returns the value in the set, if any, that is equal to the
+    /// given one, by shifting all later values down by one.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shift_take<Q: ?Sized>(&mut self, value: &Q) -> Option<T>
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.shift_remove_full(value) {
+            Some((_, k, _)) => Some(k),
+            None => None,
+        }
+    }
+}
+
+impl<T, M> PartialEq for AutoIndexSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+    /// Compares the sets' values, regardless of their order.
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter().all(|key| other.contains(key))
+    }
+}
+
+impl<T, M> Eq for AutoIndexSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+}
+
+impl<T, M> fmt::Debug for AutoIndexSet<T, M>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, M: Default> Default for AutoIndexSet<T, M> {
+    /// Creates an empty `AutoIndexSet<T, M>` with the `Default` value for the mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self {
+            map: AutoIndexMap::default(),
+        }
+    }
+}
+
+impl<T, M> FromIterator<T> for AutoIndexSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, M> Extend<T> for AutoIndexSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|k| (k, ())));
+    }
+}
+
+impl<'a, T, M> Extend<&'a T> for AutoIndexSet<T, M>
+where
+    T: 'a + Eq + AutoHash + Copy,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<'a, T, M> IntoIterator for &'a AutoIndexSet<T, M> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T, M> IntoIterator for AutoIndexSet<T, M> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out
+    /// of the set in insertion order. The set cannot be used after calling
+    /// this.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the items of an `AutoIndexSet` in insertion order.
+///
+/// This `struct` is created by the [`iter`] method on [`AutoIndexSet`].
+/// See its documentation for more.
+///
+/// [`AutoIndexSet`]: struct.AutoIndexSet.html
+/// [`iter`]: struct.AutoIndexSet.html#method.iter
+pub struct Iter<'a, K> {
+    iter: Keys<'a, K, ()>,
+}
+
+/// An owning iterator over the items of an `AutoIndexSet` in insertion order.
+///
+/// This `struct` is created by the [`into_iter`] method on [`AutoIndexSet`]
+/// (provided by the `IntoIterator` trait). See its documentation for more.
+///
+/// [`AutoIndexSet`]: struct.AutoIndexSet.html
+/// [`into_iter`]: struct.AutoIndexSet.html#method.into_iter
+pub struct IntoIter<K> {
+    iter: map::IntoIter<K, ()>,
+}
+
+/// A draining iterator over the items of an `AutoIndexSet` in insertion order.
+///
+/// This `struct` is created by the [`drain`] method on [`AutoIndexSet`].
+/// See its documentation for more.
+///
+/// [`AutoIndexSet`]: struct.AutoIndexSet.html
+/// [`drain`]: struct.AutoIndexSet.html#method.drain
+pub struct Drain<'a, K> {
+    iter: map::Drain<'a, K, ()>,
+}
+
+impl<K> Clone for Iter<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+impl<'a, K> Iterator for Iter<'a, K> {
+    type Item = &'a K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a K> {
+        self.iter.next()
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<K> DoubleEndedIterator for Iter<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+impl<K> ExactSizeIterator for Iter<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+impl<K> FusedIterator for Iter<'_, K> {}
+
+impl<K: fmt::Debug> fmt::Debug for Iter<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<K> Iterator for IntoIter<K> {
+    type Item = K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.iter.next() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<K> DoubleEndedIterator for IntoIter<K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.iter.next_back() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+}
+impl<K> ExactSizeIterator for IntoIter<K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+impl<K> FusedIterator for IntoIter<K> {}
+
+impl<K: fmt::Debug> fmt::Debug for IntoIter<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries_iter = self.iter.iter().map(|(k, _)| k);
+        f.debug_list().entries(entries_iter).finish()
+    }
+}
+
+impl<K> Iterator for Drain<'_, K> {
+    type Item = K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.iter.next() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<K> DoubleEndedIterator for Drain<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.iter.next_back() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+}
+impl<K> ExactSizeIterator for Drain<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+impl<K> FusedIterator for Drain<'_, K> {}
+
+impl<K: fmt::Debug> fmt::Debug for Drain<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries_iter = self.iter.iter().map(|(k, _)| k);
+        f.debug_list().entries(entries_iter).finish()
+    }
+}
@@ -6,13 +6,19 @@
 //! the type is already hash-like or has a saved hash, or if you don't want your
 //! type to implement `Hash` for some reason.
 //!
-//! Example key types are included in the [`wrappers`] module.
+//! Primitive integers, `bool`, `char`, tuples, and arrays implement `AutoHash`
+//! directly, so they can be used as keys as-is.
+//!
+//! Example key types are included in the [`wrappers`] module. With the `derive`
+//! feature, `AutoHash` can also be derived for structs and enums whose fields
+//! are all `AutoHash`, combining their hashes with the functions in [`mix`].
 //!
 //! [`AutoHashMap`]: map/struct.AutoHashMap.html
 //! [`AutoHashSet`]: set/struct.AutoHashSet.html
 //! [`AutoHash`]: trait.AutoHash.html
 //! [`hashbrown`]: https://crates.io/crates/hashbrown
 //! [`wrappers`]: wrappers/index.html
+//! [`mix`]: mix/index.html
 
 #![no_std]
 #![allow(
@@ -31,14 +37,27 @@
 // #[cfg_attr(test, macro_use)]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(feature = "nightly")]
 #[cfg(doctest)]
 doc_comment::doctest!("../README.md");
 
 mod external_trait_impls;
 
+#[cfg(feature = "std")]
+pub mod concurrent;
+pub mod flood;
+pub mod index;
+pub mod lru;
 pub mod map;
+pub mod mix;
+pub mod persistent;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod set;
+pub mod stored;
 pub mod wrappers;
 
 pub use crate::map::AutoHashMap;
@@ -46,16 +65,119 @@ pub use crate::set::AutoHashSet;
 
 pub use hashbrown::TryReserveError;
 
+#[cfg(feature = "derive")]
+pub use autohash_derive::AutoHash;
+
+use core::borrow::Borrow;
+
 /// A self-hashed type.
 ///
 /// Types implementing `AutoHash` are able to return their hash value independently.
 ///
 /// If two values are equal, their hashes must also be equal, even through `Borrow`.
+///
+/// This is implemented for primitive integers, `bool`, and `char` by mixing
+/// their value with [`mix::mix64`], and for tuples (up to 12 elements) and
+/// arrays (up to 32 elements) by combining their elements with
+/// [`mix::combine`]. These results are fixed, so they are stable across
+/// platforms and program runs.
+///
+/// With the `derive` feature, `#[derive(AutoHash)]` implements this by combining
+/// the hashes of all fields, which upholds that contract whenever `Eq` compares
+/// the same fields.
 pub trait AutoHash {
     /// Return the hash for this value.
     fn get_hash(&self) -> u64;
 }
 
+/// A self-hashed type with a wider 128-bit hash.
+///
+/// This is useful for keys that carry a content digest, like a 128-bit
+/// BLAKE3 or XXH3 hash. Tables are still indexed by [`AutoHash::get_hash`],
+/// which is typically [`mix::fold128`] of the wide hash, but lookups like
+/// [`AutoHashMap::get128`] will also compare the full 128-bit hash as a fast
+/// rejection before comparing keys for equality.
+///
+/// If two values are equal, their 128-bit hashes must also be equal, just like
+/// their `AutoHash` values.
+///
+/// [`AutoHashMap::get128`]: map/struct.AutoHashMap.html#method.get128
+pub trait AutoHash128: AutoHash {
+    /// Return the 128-bit hash for this value.
+    fn get_hash128(&self) -> u128;
+}
+
+impl<T: AutoHash128 + ?Sized> AutoHash128 for &'_ T {
+    #[inline]
+    fn get_hash128(&self) -> u128 {
+        T::get_hash128(*self)
+    }
+}
+
+/// Key equivalence trait.
+///
+/// This trait allows hash table lookups to be customized. It has one blanket
+/// implementation that uses the regular `Borrow` and `Eq` solution, just like
+/// the standard `HashMap` does, so you can pass `&Q` to look up a key `K` when
+/// `K: Borrow<Q>`.
+///
+/// Other implementations allow the query to be a cheap view of the key, like a
+/// tuple of references to look up a key made of owned parts. The query type
+/// must also implement `AutoHash`, and if `query.equivalent(key)` is true,
+/// then `query.get_hash()` must be equal to `key.get_hash()`.
+///
+/// # Examples
+///
+/// ```
+/// use autohash::{AutoHash, AutoHashMap, Equivalent};
+///
+/// #[derive(PartialEq, Eq)]
+/// struct Key {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// impl AutoHash for Key {
+///     fn get_hash(&self) -> u64 {
+///         self.id.get_hash()
+///     }
+/// }
+///
+/// struct KeyRef<'a>(u32, &'a str);
+///
+/// impl AutoHash for KeyRef<'_> {
+///     fn get_hash(&self) -> u64 {
+///         self.0.get_hash()
+///     }
+/// }
+///
+/// impl Equivalent<Key> for KeyRef<'_> {
+///     fn equivalent(&self, key: &Key) -> bool {
+///         self.0 == key.id && self.1 == key.name
+///     }
+/// }
+///
+/// let mut map = AutoHashMap::new();
+/// map.insert(Key { id: 1, name: "one".to_string() }, 1);
+/// assert_eq!(map.get(&KeyRef(1, "one")), Some(&1));
+/// assert_eq!(map.get(&KeyRef(1, "uno")), None);
+/// ```
+pub trait Equivalent<K: ?Sized> {
+    /// Compare this value to `key` and return `true` if they are equal.
+    fn equivalent(&self, key: &K) -> bool;
+}
+
+impl<Q: ?Sized, K: ?Sized> Equivalent<K> for Q
+where
+    Q: Eq,
+    K: Borrow<Q>,
+{
+    #[inline]
+    fn equivalent(&self, key: &K) -> bool {
+        *self == *key.borrow()
+    }
+}
+
 // The forwarding impls below match the standard library's `impl<T> Borrow<T>`.
 
 impl<T: AutoHash + ?Sized> AutoHash for &'_ T {
@@ -102,3 +224,97 @@ impl<T: AutoHash + ?Sized> AutoHash for alloc::sync::Arc<T> {
         T::get_hash(&**self)
     }
 }
+
+// Primitive values are mixed with a fixed function, since their raw bits are
+// often sequential or otherwise poorly distributed.
+
+macro_rules! impl_auto_hash_int {
+    ($($t:ty)*) => {$(
+        impl AutoHash for $t {
+            #[inline]
+            fn get_hash(&self) -> u64 {
+                mix::mix64(*self as u64)
+            }
+        }
+    )*}
+}
+
+impl_auto_hash_int! { u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+
+macro_rules! impl_auto_hash_int128 {
+    ($($t:ty)*) => {$(
+        impl AutoHash for $t {
+            #[inline]
+            fn get_hash(&self) -> u64 {
+                let high = mix::mix64((*self >> 64) as u64);
+                mix::mix64(*self as u64 ^ high)
+            }
+        }
+    )*}
+}
+
+impl_auto_hash_int128! { u128 i128 }
+
+impl AutoHash for bool {
+    #[inline]
+    fn get_hash(&self) -> u64 {
+        mix::mix64(*self as u64)
+    }
+}
+
+impl AutoHash for char {
+    #[inline]
+    fn get_hash(&self) -> u64 {
+        mix::mix64(*self as u64)
+    }
+}
+
+// Tuples and arrays combine their elements in order, just like the derived
+// implementation for a tuple struct.
+
+macro_rules! impl_auto_hash_tuple {
+    ($(($($T:ident)*))*) => {$(
+        impl<$($T: AutoHash),*> AutoHash for ($($T,)*) {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn get_hash(&self) -> u64 {
+                let ($($T,)*) = self;
+                let hash = 0;
+                $(let hash = mix::combine(hash, $T.get_hash());)*
+                hash
+            }
+        }
+    )*}
+}
+
+impl_auto_hash_tuple! {
+    ()
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
+    (A B C D E F G H I)
+    (A B C D E F G H I J)
+    (A B C D E F G H I J K)
+    (A B C D E F G H I J K L)
+}
+
+macro_rules! impl_auto_hash_array {
+    ($($N:literal)*) => {$(
+        impl<T: AutoHash> AutoHash for [T; $N] {
+            #[inline]
+            fn get_hash(&self) -> u64 {
+                self.iter().fold(0, |hash, x| mix::combine(hash, x.get_hash()))
+            }
+        }
+    )*}
+}
+
+impl_auto_hash_array! {
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+    17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+}
@@ -0,0 +1,555 @@
+//! A bounded cache that evicts the least recently used entry.
+//!
+//! [`AutoLruCache`] looks up keys by their own [`AutoHash`] value, like an
+//! [`AutoHashMap`], but holds at most a fixed number of entries. The entries
+//! are linked into a list in order of use, so finding and evicting the least
+//! recently used one takes constant time.
+//!
+//! [`AutoLruCache`]: struct.AutoLruCache.html
+//! [`AutoHash`]: ../trait.AutoHash.html
+//! [`AutoHashMap`]: ../map/struct.AutoHashMap.html
+
+use crate::mix::{HashMixer, NoMix};
+use crate::{AutoHash, Equivalent};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+use core::mem;
+use hashbrown::raw::RawTable;
+
+/// Marks the end of the recency list.
+const NIL: usize = usize::MAX;
+
+/// A cache holding at most `cap` entries, which evicts the least recently
+/// used entry to make room for a new one.
+///
+/// The entries are stored densely in a `Vec`, and each one holds the
+/// positions of its neighbors in a doubly-linked list, from the most to the
+/// least recently used. A [`RawTable`] of positions is keyed by each key's
+/// mixed [`AutoHash`] value, which is saved in the entry so the table never
+/// needs to call `get_hash` again when it grows.
+///
+/// [`get`] and [`get_mut`] mark an entry as the most recently used, while
+/// [`peek`] and [`contains_key`] leave the order alone.
+///
+/// [`RawTable`]: https://docs.rs/hashbrown/0.9/hashbrown/raw/struct.RawTable.html
+/// [`AutoHash`]: ../trait.AutoHash.html
+/// [`get`]: #method.get
+/// [`get_mut`]: #method.get_mut
+/// [`peek`]: #method.peek
+/// [`contains_key`]: #method.contains_key
+///
+/// # Examples
+///
+/// ```
+/// use autohash::lru::AutoLruCache;
+///
+/// let mut cache = AutoLruCache::new(2);
+/// assert_eq!(cache.put(1, "one"), None);
+/// assert_eq!(cache.put(2, "two"), None);
+///
+/// // Reading 1 makes 2 the least recently used entry.
+/// assert_eq!(cache.get(&1), Some(&"one"));
+/// assert_eq!(cache.put(3, "three"), Some((2, "two")));
+///
+/// let keys: Vec<_> = cache.iter().map(|(k, _)| *k).collect();
+/// assert_eq!(keys, [3, 1]);
+/// ```
+pub struct AutoLruCache<K, V, M = NoMix> {
+    mixer: M,
+    table: RawTable<usize>,
+    nodes: Vec<Node<K, V>>,
+    head: usize,
+    tail: usize,
+    cap: usize,
+}
+
+#[derive(Clone)]
+struct Node<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+impl<K: Clone, V: Clone, M: Clone> Clone for AutoLruCache<K, V, M> {
+    fn clone(&self) -> Self {
+        AutoLruCache {
+            mixer: self.mixer.clone(),
+            table: self.table.clone(),
+            nodes: self.nodes.clone(),
+            head: self.head,
+            tail: self.tail,
+            cap: self.cap,
+        }
+    }
+}
+
+/// Rehashes a position by reading the hash stored in its node.
+#[cfg_attr(feature = "inline-more", inline)]
+fn stored_hash<K, V>(nodes: &[Node<K, V>]) -> impl Fn(&usize) -> u64 + '_ {
+    move |&i| nodes[i].hash
+}
+
+/// Compares the stored hash before calling `Equivalent` on the keys.
+#[cfg_attr(feature = "inline-more", inline)]
+fn equivalent_key<'a, Q, K, V>(
+    hash: u64,
+    k: &'a Q,
+    nodes: &'a [Node<K, V>],
+) -> impl Fn(&usize) -> bool + 'a
+where
+    Q: ?Sized + Equivalent<K>,
+{
+    move |&i| {
+        let node = &nodes[i];
+        hash == node.hash && k.equivalent(&node.key)
+    }
+}
+
+/// Finds the table slot that refers to a particular position.
+#[cfg_attr(feature = "inline-more", inline)]
+fn equivalent_index(index: usize) -> impl Fn(&usize) -> bool {
+    move |&i| i == index
+}
+
+impl<K, V> AutoLruCache<K, V, NoMix> {
+    /// Creates an empty `AutoLruCache` that holds at most `cap` entries.
+    ///
+    /// The cache does not allocate until it is first inserted into. A cache
+    /// with a capacity of 0 never holds any entries.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new(cap: usize) -> Self {
+        Self::with_mixer(cap, NoMix)
+    }
+}
+
+impl<K, V, M> AutoLruCache<K, V, M> {
+    /// Creates an empty `AutoLruCache` that holds at most `cap` entries, and
+    /// will use the given mixer to post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(cap: usize, mixer: M) -> Self {
+        Self {
+            mixer,
+            table: RawTable::new(),
+            nodes: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            cap,
+        }
+    }
+
+    /// Returns a reference to the cache's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        &self.mixer
+    }
+
+    /// Returns the maximum number of entries the cache can hold.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the number of entries in the cache.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the cache contains no entries.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// An iterator visiting all key-value pairs from the most recently used
+    /// to the least recently used, without changing their order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            nodes: &self.nodes,
+            front: self.head,
+            back: self.tail,
+            len: self.nodes.len(),
+        }
+    }
+
+    /// Returns the least recently used entry, which is the next to be
+    /// evicted, without changing the order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.nodes.get(self.tail) {
+            Some(node) => Some((&node.key, &node.value)),
+            None => None,
+        }
+    }
+
+    /// Removes and returns the least recently used entry.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        if self.tail == NIL {
+            return None;
+        }
+        Some(self.remove_node(self.tail))
+    }
+
+    /// Changes the maximum number of entries, evicting the least recently
+    /// used entries until the cache fits within the new capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::lru::AutoLruCache;
+    ///
+    /// let mut cache = AutoLruCache::new(3);
+    /// cache.put('a', 1);
+    /// cache.put('b', 2);
+    /// cache.put('c', 3);
+    ///
+    /// cache.resize(1);
+    /// assert_eq!(cache.len(), 1);
+    /// assert_eq!(cache.peek_lru(), Some((&'c', &3)));
+    /// ```
+    pub fn resize(&mut self, cap: usize) {
+        while self.nodes.len() > cap {
+            self.pop_lru();
+        }
+        self.cap = cap;
+    }
+
+    /// Clears the cache, removing all entries. Keeps the allocated memory for
+    /// reuse.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn clear(&mut self) {
+        self.table.clear_no_drop();
+        self.nodes.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    /// Removes a node from the recency list, joining its neighbors.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn unlink(&mut self, index: usize) {
+        let (prev, next) = {
+            let node = &self.nodes[index];
+            (node.prev, node.next)
+        };
+        match prev {
+            NIL => self.head = next,
+            _ => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            _ => self.nodes[next].prev = prev,
+        }
+    }
+
+    /// Links an unlinked node at the front of the recency list.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn push_front(&mut self, index: usize) {
+        let head = self.head;
+        {
+            let node = &mut self.nodes[index];
+            node.prev = NIL;
+            node.next = head;
+        }
+        match head {
+            NIL => self.tail = index,
+            _ => self.nodes[head].prev = index,
+        }
+        self.head = index;
+    }
+
+    /// Marks a node as the most recently used.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn touch(&mut self, index: usize) {
+        if self.head != index {
+            self.unlink(index);
+            self.push_front(index);
+        }
+    }
+
+    /// Removes a node from the table, the list, and the `Vec`, moving the last
+    /// node into its position.
+    fn remove_node(&mut self, index: usize) -> (K, V) {
+        let hash = self.nodes[index].hash;
+        self.table.erase_entry(hash, equivalent_index(index));
+        self.unlink(index);
+
+        let node = self.nodes.swap_remove(index);
+        let last = self.nodes.len();
+        if index < last {
+            // Point the moved node's neighbors and table slot at its new position.
+            let (hash, prev, next) = {
+                let moved = &self.nodes[index];
+                (moved.hash, moved.prev, moved.next)
+            };
+            match prev {
+                NIL => self.head = index,
+                _ => self.nodes[prev].next = index,
+            }
+            match next {
+                NIL => self.tail = index,
+                _ => self.nodes[next].prev = index,
+            }
+            let slot = self.table.get_mut(hash, equivalent_index(last));
+            *slot.expect("table slot not found for moved node") = index;
+        }
+        (node.key, node.value)
+    }
+}
+
+impl<K, V, M> AutoLruCache<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn hash<Q: AutoHash + ?Sized>(&self, k: &Q) -> u64 {
+        self.mixer.mix(k.get_hash())
+    }
+
+    #[inline]
+    fn find<Q: ?Sized>(&self, k: &Q) -> Option<usize>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.hash(k);
+        self.table
+            .get(hash, equivalent_key(hash, k, &self.nodes))
+            .copied()
+    }
+
+    /// Returns a reference to the value corresponding to the key, and marks
+    /// it as the most recently used.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the cache's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[inline]
+    pub fn get<Q: ?Sized>(&mut self, k: &Q) -> Option<&V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        match self.find(k) {
+            Some(index) => {
+                self.touch(index);
+                Some(&self.nodes[index].value)
+            }
+            None => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, and
+    /// marks it as the most recently used.
+    #[inline]
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        match self.find(k) {
+            Some(index) => {
+                self.touch(index);
+                Some(&mut self.nodes[index].value)
+            }
+            None => None,
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key, without
+    /// changing the order of use.
+    #[inline]
+    pub fn peek<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.find(k) {
+            Some(index) => Some(&self.nodes[index].value),
+            None => None,
+        }
+    }
+
+    /// Returns `true` if the cache contains a value for the specified key,
+    /// without changing the order of use.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        self.find(k).is_some()
+    }
+
+    /// Inserts a key-value pair as the most recently used entry.
+    ///
+    /// If the cache already had this key present, its value is replaced, and
+    /// the given key is returned with the old value. The stored key is not
+    /// updated, though.
+    ///
+    /// Otherwise, if the cache was full, the least recently used entry is
+    /// evicted and returned. With a capacity of 0, the new pair itself is
+    /// returned.
+    pub fn put(&mut self, k: K, v: V) -> Option<(K, V)> {
+        let hash = self.hash(&k);
+        if let Some(&index) = self.table.get(hash, equivalent_key(hash, &k, &self.nodes)) {
+            self.touch(index);
+            let old = mem::replace(&mut self.nodes[index].value, v);
+            return Some((k, old));
+        }
+
+        if self.cap == 0 {
+            return Some((k, v));
+        }
+
+        let node = Node {
+            hash,
+            key: k,
+            value: v,
+            prev: NIL,
+            next: NIL,
+        };
+        if self.nodes.len() >= self.cap {
+            // Reuse the least recently used node's position for the new entry.
+            let index = self.tail;
+            self.table
+                .erase_entry(self.nodes[index].hash, equivalent_index(index));
+            self.unlink(index);
+            let old = mem::replace(&mut self.nodes[index], node);
+            self.table.insert(hash, index, stored_hash(&self.nodes));
+            self.push_front(index);
+            Some((old.key, old.value))
+        } else {
+            let index = self.nodes.len();
+            self.table.insert(hash, index, stored_hash(&self.nodes));
+            self.nodes.push(node);
+            self.push_front(index);
+            None
+        }
+    }
+
+    /// Removes a key from the cache, returning the value at the key if the
+    /// key was previously in the cache.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.remove_entry(k) {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Removes a key from the cache, returning the stored key and value if
+    /// the key was previously in the cache.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove_entry<Q: ?Sized>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        match self.find(k) {
+            Some(index) => Some(self.remove_node(index)),
+            None => None,
+        }
+    }
+}
+
+impl<K, V, M> Debug for AutoLruCache<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a AutoLruCache<K, V, M> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of an `AutoLruCache`, from the most recently
+/// used to the least recently used.
+///
+/// This `struct` is created by the [`iter`] method on [`AutoLruCache`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.AutoLruCache.html#method.iter
+/// [`AutoLruCache`]: struct.AutoLruCache.html
+pub struct Iter<'a, K, V> {
+    nodes: &'a [Node<K, V>],
+    front: usize,
+    back: usize,
+    len: usize,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Iter {
+            nodes: self.nodes,
+            front: self.front,
+            back: self.back,
+            len: self.len,
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = &self.nodes[self.front];
+        self.front = node.next;
+        self.len -= 1;
+        Some((&node.key, &node.value))
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = &self.nodes[self.back];
+        self.back = node.prev;
+        self.len -= 1;
+        Some((&node.key, &node.value))
+    }
+}
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
@@ -1,8 +1,8 @@
 //! A hash map implemented with quadratic probing and SIMD lookup.
 
-use crate::AutoHash;
+use crate::mix::{HashMixer, KeyedMix, NoMix};
 use crate::TryReserveError;
-use core::borrow::Borrow;
+use crate::{AutoHash, AutoHash128, Equivalent};
 use core::fmt::{self, Debug};
 use core::iter::{FromIterator, FusedIterator};
 use core::marker::PhantomData;
@@ -179,25 +179,47 @@ use hashbrown::raw::{Bucket, RawDrain, RawIntoIter, RawIter, RawTable};
 ///     .iter().cloned().collect();
 /// // use the values stored in map
 /// ```
-pub struct AutoHashMap<K, V> {
+pub struct AutoHashMap<K, V, M = NoMix> {
+    pub(crate) mixer: M,
     pub(crate) table: RawTable<(K, V)>,
 }
 
-impl<K: Clone, V: Clone> Clone for AutoHashMap<K, V> {
+impl<K: Clone, V: Clone, M: Clone> Clone for AutoHashMap<K, V, M> {
     fn clone(&self) -> Self {
         AutoHashMap {
+            mixer: self.mixer.clone(),
             table: self.table.clone(),
         }
     }
 
     fn clone_from(&mut self, source: &Self) {
         self.table.clone_from(&source.table);
+
+        // Update mixer after table, in case the table clone panics.
+        self.mixer.clone_from(&source.mixer);
     }
 }
 
-#[inline]
-pub(crate) fn entry_hash<K: AutoHash, V>(&(ref key, _): &(K, V)) -> u64 {
-    key.get_hash()
+/// Computes the hash of a key as used by the table: its `AutoHash` value,
+/// post-processed by the map's `HashMixer`.
+#[cfg_attr(feature = "inline-more", inline)]
+pub(crate) fn make_hash<K, M>(mixer: &M, key: &K) -> u64
+where
+    K: AutoHash + ?Sized,
+    M: HashMixer,
+{
+    mixer.mix(key.get_hash())
+}
+
+/// Ensures that a single closure type across uses of this which, in turn prevents multiple
+/// instances of any functions like RawTable::reserve from being generated
+#[cfg_attr(feature = "inline-more", inline)]
+pub(crate) fn make_hasher<K, V, M>(mixer: &M) -> impl Fn(&(K, V)) -> u64 + '_
+where
+    K: AutoHash,
+    M: HashMixer,
+{
+    move |val| make_hash(mixer, &val.0)
 }
 
 /// Ensures that a single closure type across uses of this which, in turn prevents multiple
@@ -205,10 +227,20 @@ pub(crate) fn entry_hash<K: AutoHash, V>(&(ref key, _): &(K, V)) -> u64 {
 #[cfg_attr(feature = "inline-more", inline)]
 fn equivalent_key<Q, K, V>(k: &Q) -> impl Fn(&(K, V)) -> bool + '_
 where
-    K: Borrow<Q>,
-    Q: ?Sized + Eq,
+    Q: ?Sized + Equivalent<K>,
+{
+    move |x| k.equivalent(&x.0)
+}
+
+/// Like `equivalent_key`, but first rejects keys with a different 128-bit hash.
+#[cfg_attr(feature = "inline-more", inline)]
+fn equivalent_key128<Q, K, V>(k: &Q) -> impl Fn(&(K, V)) -> bool + '_
+where
+    Q: ?Sized + AutoHash128 + Equivalent<K>,
+    K: AutoHash128,
 {
-    move |x| k.eq(x.0.borrow())
+    let hash = k.get_hash128();
+    move |x| hash == x.0.get_hash128() && k.equivalent(&x.0)
 }
 
 /// Ensures that a single closure type across uses of this which, in turn prevents multiple
@@ -216,13 +248,12 @@ where
 #[cfg_attr(feature = "inline-more", inline)]
 fn equivalent<Q, K>(k: &Q) -> impl Fn(&K) -> bool + '_
 where
-    K: Borrow<Q>,
-    Q: ?Sized + Eq,
+    Q: ?Sized + Equivalent<K>,
 {
-    move |x| k.eq(x.borrow())
+    move |x| k.equivalent(x)
 }
 
-impl<K, V> AutoHashMap<K, V> {
+impl<K, V> AutoHashMap<K, V, NoMix> {
     /// Creates an empty `AutoHashMap`.
     ///
     /// The hash map is initially created with a capacity of 0, so it will not allocate until it
@@ -236,9 +267,7 @@ impl<K, V> AutoHashMap<K, V> {
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn new() -> Self {
-        Self {
-            table: RawTable::new(),
-        }
+        Self::with_mixer(NoMix)
     }
 
     /// Creates an empty `AutoHashMap` with the specified capacity.
@@ -254,11 +283,120 @@ impl<K, V> AutoHashMap<K, V> {
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_mixer(capacity, NoMix)
+    }
+}
+
+impl<K, V> AutoHashMap<K, V, KeyedMix> {
+    /// Creates an empty `AutoHashMap` with a [`KeyedMix`] derived from `seed`.
+    ///
+    /// If the seed is kept secret, keys with attacker-chosen hashes, like
+    /// `RawHashed` values from untrusted input, cannot be aimed at the same
+    /// buckets to degrade the performance of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::wrappers::RawHashed;
+    /// use autohash::AutoHashMap;
+    ///
+    /// let mut map = AutoHashMap::with_seed(0x5eed);
+    /// map.insert(RawHashed::new(0, "zero"), 0);
+    /// assert_eq!(map.get(&RawHashed::new(0, "zero")), Some(&0));
+    /// ```
+    ///
+    /// [`KeyedMix`]: ../mix/struct.KeyedMix.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_mixer(KeyedMix::new(seed))
+    }
+
+    /// Creates an empty `AutoHashMap` with a randomly keyed [`KeyedMix`].
+    ///
+    /// This is only available with the `getrandom` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a random seed could not be read from the operating system.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::wrappers::U64Hash;
+    /// use autohash::AutoHashMap;
+    ///
+    /// let mut map = AutoHashMap::new_randomized();
+    /// map.insert(U64Hash(1), "a");
+    /// assert_eq!(map[&U64Hash(1)], "a");
+    /// ```
+    ///
+    /// [`KeyedMix`]: ../mix/struct.KeyedMix.html
+    #[cfg(feature = "getrandom")]
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new_randomized() -> Self {
+        Self::with_mixer(KeyedMix::random())
+    }
+}
+
+impl<K, V, M> AutoHashMap<K, V, M> {
+    /// Creates an empty `AutoHashMap` which will use the given mixer to
+    /// post-process the hashes of its keys.
+    ///
+    /// The hash map is initially created with a capacity of 0, so it will not allocate until it
+    /// is first inserted into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::mix::Murmur3Mix;
+    /// use autohash::wrappers::U64Hash;
+    /// use autohash::AutoHashMap;
+    ///
+    /// let mut map = AutoHashMap::with_mixer(Murmur3Mix);
+    /// map.insert(U64Hash(1), "a");
+    /// assert_eq!(map.get(&U64Hash(1)), Some(&"a"));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self {
+            mixer,
+            table: RawTable::new(),
+        }
+    }
+
+    /// Creates an empty `AutoHashMap` with the specified capacity, which will
+    /// use the given mixer to post-process the hashes of its keys.
+    ///
+    /// The hash map will be able to hold at least `capacity` elements without
+    /// reallocating. If `capacity` is 0, the hash map will not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::mix::SeededMix;
+    /// use autohash::wrappers::U64Hash;
+    /// use autohash::AutoHashMap;
+    ///
+    /// let mut map = AutoHashMap::with_capacity_and_mixer(10, SeededMix::new(42));
+    /// map.insert(U64Hash(1), "a");
+    /// assert!(map.capacity() >= 10);
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_and_mixer(capacity: usize, mixer: M) -> Self {
         Self {
+            mixer,
             table: RawTable::with_capacity(capacity),
         }
     }
 
+    /// Returns a reference to the map's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        &self.mixer
+    }
+
     /// Returns the number of elements the map can hold without reallocating.
     ///
     /// This number is a lower bound; the `AutoHashMap<K, V>` might be able to hold
@@ -564,9 +702,10 @@ impl<K, V> AutoHashMap<K, V> {
     }
 }
 
-impl<K, V> AutoHashMap<K, V>
+impl<K, V, M> AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash,
+    M: HashMixer,
 {
     /// Reserves capacity for at least `additional` more elements to be inserted
     /// in the `AutoHashMap`. The collection may reserve more space to avoid
@@ -587,7 +726,7 @@ where
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn reserve(&mut self, additional: usize) {
-        self.table.reserve(additional, entry_hash);
+        self.table.reserve(additional, make_hasher(&self.mixer));
     }
 
     /// Tries to reserve capacity for at least `additional` more elements to be inserted
@@ -608,7 +747,7 @@ where
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
-        self.table.try_reserve(additional, entry_hash)
+        self.table.try_reserve(additional, make_hasher(&self.mixer))
     }
 
     /// Shrinks the capacity of the map as much as possible. It will drop
@@ -629,7 +768,7 @@ where
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn shrink_to_fit(&mut self) {
-        self.table.shrink_to(0, entry_hash);
+        self.table.shrink_to(0, make_hasher(&self.mixer));
     }
 
     /// Shrinks the capacity of the map with a lower limit. It will drop
@@ -657,7 +796,7 @@ where
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn shrink_to(&mut self, min_capacity: usize) {
-        self.table.shrink_to(min_capacity, entry_hash);
+        self.table.shrink_to(min_capacity, make_hasher(&self.mixer));
     }
 
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
@@ -680,8 +819,8 @@ where
     /// assert_eq!(letters.get(&'y'), None);
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
-        let hash = key.get_hash();
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, M> {
+        let hash = make_hash(&self.mixer, &key);
         if let Some(elem) = self.table.find(hash, equivalent_key(&key)) {
             Entry::Occupied(OccupiedEntry {
                 hash,
@@ -700,12 +839,12 @@ where
 
     /// Returns a reference to the value corresponding to the key.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     ///
     /// # Examples
     ///
@@ -720,8 +859,7 @@ where
     #[inline]
     pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         // Avoid `Option::map` because it bloats LLVM IR.
         match self.get_inner(k) {
@@ -732,12 +870,12 @@ where
 
     /// Returns the key-value pair corresponding to the supplied key.
     ///
-    /// The supplied key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The supplied key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     ///
     /// # Examples
     ///
@@ -752,8 +890,7 @@ where
     #[inline]
     pub fn get_key_value<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         // Avoid `Option::map` because it bloats LLVM IR.
         match self.get_inner(k) {
@@ -765,20 +902,19 @@ where
     #[inline]
     fn get_inner<Q: ?Sized>(&self, k: &Q) -> Option<&(K, V)>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
-        self.table.get(k.get_hash(), equivalent_key(k))
+        self.table.get(make_hash(&self.mixer, k), equivalent_key(k))
     }
 
     /// Returns the key-value pair corresponding to the supplied key, with a mutable reference to value.
     ///
-    /// The supplied key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The supplied key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     ///
     /// # Examples
     ///
@@ -797,8 +933,7 @@ where
     #[inline]
     pub fn get_key_value_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<(&K, &mut V)>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         // Avoid `Option::map` because it bloats LLVM IR.
         match self.get_inner_mut(k) {
@@ -809,12 +944,12 @@ where
 
     /// Returns `true` if the map contains a value for the specified key.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     ///
     /// # Examples
     ///
@@ -829,20 +964,19 @@ where
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         self.get_inner(k).is_some()
     }
 
     /// Returns a mutable reference to the value corresponding to the key.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     ///
     /// # Examples
     ///
@@ -859,8 +993,7 @@ where
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         // Avoid `Option::map` because it bloats LLVM IR.
         match self.get_inner_mut(k) {
@@ -872,10 +1005,10 @@ where
     #[inline]
     fn get_inner_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut (K, V)>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
-        self.table.get_mut(k.get_hash(), equivalent_key(k))
+        self.table
+            .get_mut(make_hash(&self.mixer, k), equivalent_key(k))
     }
 
     /// Inserts a key-value pair into the map.
@@ -905,11 +1038,11 @@ where
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn insert(&mut self, k: K, v: V) -> Option<V> {
-        let hash = k.get_hash();
+        let hash = make_hash(&self.mixer, &k);
         if let Some((_, item)) = self.table.get_mut(hash, equivalent_key(&k)) {
             Some(mem::replace(item, v))
         } else {
-            self.table.insert(hash, (k, v), entry_hash);
+            self.table.insert(hash, (k, v), make_hasher(&self.mixer));
             None
         }
     }
@@ -917,12 +1050,12 @@ where
     /// Removes a key from the map, returning the value at the key if the key
     /// was previously in the map.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     ///
     /// # Examples
     ///
@@ -937,8 +1070,7 @@ where
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         // Avoid `Option::map` because it bloats LLVM IR.
         match self.remove_entry(k) {
@@ -950,12 +1082,12 @@ where
     /// Removes a key from the map, returning the stored key and value if the
     /// key was previously in the map.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     ///
     /// # Examples
     ///
@@ -970,15 +1102,249 @@ where
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn remove_entry<Q: ?Sized>(&mut self, k: &Q) -> Option<(K, V)>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
-        let hash = k.get_hash();
+        let hash = make_hash(&self.mixer, k);
         self.table.remove_entry(hash, equivalent_key(k))
     }
+
+    /// Moves all entries from `other` into the map, calling `f` to combine
+    /// the values of any key that is present in both.
+    ///
+    /// The values are passed as `f(&key, value_in_self, value_in_other)`, and
+    /// the map keeps its own copy of the key. Each key in `other` is hashed
+    /// only once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    ///
+    /// let mut a: AutoHashMap<u32, u32> = [(1, 10), (2, 20)].iter().copied().collect();
+    /// let b: AutoHashMap<u32, u32> = [(2, 2), (3, 3)].iter().copied().collect();
+    ///
+    /// a.merge_with(b, |_, x, y| x + y);
+    /// assert_eq!(a.len(), 3);
+    /// assert_eq!(a[&1], 10);
+    /// assert_eq!(a[&2], 22);
+    /// assert_eq!(a[&3], 3);
+    /// ```
+    pub fn merge_with<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, V, V) -> V,
+    {
+        // Keys may already be present, so this uses the same reservation
+        // as `extend`.
+        let reserve = if self.is_empty() {
+            other.len()
+        } else {
+            (other.len() + 1) / 2
+        };
+        self.reserve(reserve);
+        for (k, v) in other {
+            let hash = make_hash(&self.mixer, &k);
+            match self.table.find(hash, equivalent_key(&k)) {
+                Some(bucket) => unsafe {
+                    self.table.replace_bucket_with(bucket, |(key, value)| {
+                        let value = f(&key, value, v);
+                        Some((key, value))
+                    });
+                },
+                None => {
+                    self.table.insert(hash, (k, v), make_hasher(&self.mixer));
+                }
+            }
+        }
+    }
+}
+
+impl<K, V, M> AutoHashMap<K, V, M>
+where
+    K: Eq + AutoHash128,
+    M: HashMixer,
+{
+    /// Returns a reference to the value corresponding to the key, comparing
+    /// the full 128-bit hash before calling [`Equivalent`].
+    ///
+    /// Keys whose 128-bit hash differs from the query are rejected without
+    /// comparing their values, which is cheap for keys that store a digest.
+    ///
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    /// use autohash::wrappers::U128Hash;
+    ///
+    /// let mut map = AutoHashMap::new();
+    /// map.insert(U128Hash(1 << 100), "a");
+    /// assert_eq!(map.get128(&U128Hash(1 << 100)), Some(&"a"));
+    /// assert_eq!(map.get128(&U128Hash(1 << 101)), None);
+    /// ```
+    #[inline]
+    pub fn get128<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        Q: AutoHash128 + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_inner128(k) {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key, comparing
+    /// the full 128-bit hash before calling [`Equivalent`].
+    ///
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    /// use autohash::wrappers::U128Hash;
+    ///
+    /// let mut map = AutoHashMap::new();
+    /// map.insert(U128Hash(1), "a");
+    /// assert_eq!(map.get_key_value128(&U128Hash(1)), Some((&U128Hash(1), &"a")));
+    /// assert_eq!(map.get_key_value128(&U128Hash(2)), None);
+    /// ```
+    #[inline]
+    pub fn get_key_value128<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        Q: AutoHash128 + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_inner128(k) {
+            Some((key, value)) => Some((key, value)),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn get_inner128<Q: ?Sized>(&self, k: &Q) -> Option<&(K, V)>
+    where
+        Q: AutoHash128 + Equivalent<K>,
+    {
+        self.table
+            .get(make_hash(&self.mixer, k), equivalent_key128(k))
+    }
+
+    /// Returns `true` if the map contains a value for the specified key,
+    /// comparing the full 128-bit hash before calling [`Equivalent`].
+    ///
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    /// use autohash::wrappers::U128Hash;
+    ///
+    /// let mut map = AutoHashMap::new();
+    /// map.insert(U128Hash(1), "a");
+    /// assert_eq!(map.contains_key128(&U128Hash(1)), true);
+    /// assert_eq!(map.contains_key128(&U128Hash(2)), false);
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains_key128<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        Q: AutoHash128 + Equivalent<K>,
+    {
+        self.get_inner128(k).is_some()
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key,
+    /// comparing the full 128-bit hash before calling [`Equivalent`].
+    ///
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    /// use autohash::wrappers::U128Hash;
+    ///
+    /// let mut map = AutoHashMap::new();
+    /// map.insert(U128Hash(1), "a");
+    /// if let Some(x) = map.get_mut128(&U128Hash(1)) {
+    ///     *x = "b";
+    /// }
+    /// assert_eq!(map[&U128Hash(1)], "b");
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_mut128<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        Q: AutoHash128 + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self
+            .table
+            .get_mut(make_hash(&self.mixer, k), equivalent_key128(k))
+        {
+            Some(&mut (_, ref mut v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map, comparing the full 128-bit hash before
+    /// calling [`Equivalent`].
+    ///
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    /// use autohash::wrappers::U128Hash;
+    ///
+    /// let mut map = AutoHashMap::new();
+    /// map.insert(U128Hash(1), "a");
+    /// assert_eq!(map.remove128(&U128Hash(1)), Some("a"));
+    /// assert_eq!(map.remove128(&U128Hash(1)), None);
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove128<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        Q: AutoHash128 + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.remove_entry128(k) {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map, comparing the full 128-bit hash before
+    /// calling [`Equivalent`].
+    ///
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::AutoHashMap;
+    /// use autohash::wrappers::U128Hash;
+    ///
+    /// let mut map = AutoHashMap::new();
+    /// map.insert(U128Hash(1), "a");
+    /// assert_eq!(map.remove_entry128(&U128Hash(1)), Some((U128Hash(1), "a")));
+    /// assert_eq!(map.remove128(&U128Hash(1)), None);
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove_entry128<Q: ?Sized>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        Q: AutoHash128 + Equivalent<K>,
+    {
+        let hash = make_hash(&self.mixer, k);
+        self.table.remove_entry(hash, equivalent_key128(k))
+    }
 }
 
-impl<K, V> AutoHashMap<K, V> {
+impl<K, V, M> AutoHashMap<K, V, M> {
     /// Creates a raw entry builder for the HashMap.
     ///
     /// Raw entries provide the lowest level of control for searching and
@@ -990,7 +1356,7 @@ impl<K, V> AutoHashMap<K, V> {
     ///
     /// * Hash memoization
     /// * Deferring the creation of an owned key until it is known to be required
-    /// * Using a search key that doesn't work with the Borrow trait
+    /// * Using a search key that doesn't work with the `Equivalent` trait
     /// * Using custom comparison logic without newtype wrappers
     ///
     /// Because raw entries provide much more low-level control, it's much easier
@@ -1011,7 +1377,7 @@ impl<K, V> AutoHashMap<K, V> {
     /// acting erratically, with two keys randomly masking each other. Implementations
     /// are free to assume this doesn't happen (within the limits of memory-safety).
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, K, V> {
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, K, V, M> {
         RawEntryBuilderMut { map: self }
     }
 
@@ -1023,7 +1389,7 @@ impl<K, V> AutoHashMap<K, V> {
     ///
     /// This is useful for
     /// * Hash memoization
-    /// * Using a search key that doesn't work with the Borrow trait
+    /// * Using a search key that doesn't work with the `Equivalent` trait
     /// * Using custom comparison logic without newtype wrappers
     ///
     /// Unless you are in such a situation, higher-level and more foolproof APIs like
@@ -1031,15 +1397,16 @@ impl<K, V> AutoHashMap<K, V> {
     ///
     /// Immutable raw entries have very limited use; you might instead want `raw_entry_mut`.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn raw_entry(&self) -> RawEntryBuilder<'_, K, V> {
+    pub fn raw_entry(&self) -> RawEntryBuilder<'_, K, V, M> {
         RawEntryBuilder { map: self }
     }
 }
 
-impl<K, V> PartialEq for AutoHashMap<K, V>
+impl<K, V, M> PartialEq for AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash,
     V: PartialEq,
+    M: HashMixer,
 {
     fn eq(&self, other: &Self) -> bool {
         if self.len() != other.len() {
@@ -1051,14 +1418,15 @@ where
     }
 }
 
-impl<K, V> Eq for AutoHashMap<K, V>
+impl<K, V, M> Eq for AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash,
     V: Eq,
+    M: HashMixer,
 {
 }
 
-impl<K, V> Debug for AutoHashMap<K, V>
+impl<K, V, M> Debug for AutoHashMap<K, V, M>
 where
     K: Debug,
     V: Debug,
@@ -1068,18 +1436,19 @@ where
     }
 }
 
-impl<K, V> Default for AutoHashMap<K, V> {
-    /// Creates an empty `AutoHashMap<K, V>`
+impl<K, V, M: Default> Default for AutoHashMap<K, V, M> {
+    /// Creates an empty `AutoHashMap<K, V, M>`, with the `Default` value for the mixer.
     #[cfg_attr(feature = "inline-more", inline)]
     fn default() -> Self {
-        Self::new()
+        Self::with_mixer(M::default())
     }
 }
 
-impl<K, Q: ?Sized, V> Index<&Q> for AutoHashMap<K, V>
+impl<K, Q: ?Sized, V, M> Index<&Q> for AutoHashMap<K, V, M>
 where
-    K: Eq + AutoHash + Borrow<Q>,
-    Q: Eq + AutoHash,
+    K: Eq + AutoHash,
+    Q: AutoHash + Equivalent<K>,
+    M: HashMixer,
 {
     type Output = V;
 
@@ -1347,8 +1716,8 @@ pub struct ValuesMut<'a, K, V> {
 /// See the [`HashMap::raw_entry_mut`] docs for usage examples.
 ///
 /// [`HashMap::raw_entry_mut`]: struct.HashMap.html#method.raw_entry_mut
-pub struct RawEntryBuilderMut<'a, K, V> {
-    map: &'a mut AutoHashMap<K, V>,
+pub struct RawEntryBuilderMut<'a, K, V, M = NoMix> {
+    map: &'a mut AutoHashMap<K, V, M>,
 }
 
 /// A view into a single entry in a map, which may either be vacant or occupied.
@@ -1362,32 +1731,35 @@ pub struct RawEntryBuilderMut<'a, K, V> {
 /// [`Entry`]: enum.Entry.html
 /// [`raw_entry_mut`]: struct.HashMap.html#method.raw_entry_mut
 /// [`RawEntryBuilderMut`]: struct.RawEntryBuilderMut.html
-pub enum RawEntryMut<'a, K, V> {
+pub enum RawEntryMut<'a, K, V, M = NoMix> {
     /// An occupied entry.
-    Occupied(RawOccupiedEntryMut<'a, K, V>),
+    Occupied(RawOccupiedEntryMut<'a, K, V, M>),
     /// A vacant entry.
-    Vacant(RawVacantEntryMut<'a, K, V>),
+    Vacant(RawVacantEntryMut<'a, K, V, M>),
 }
 
 /// A view into an occupied entry in a `HashMap`.
 /// It is part of the [`RawEntryMut`] enum.
 ///
 /// [`RawEntryMut`]: enum.RawEntryMut.html
-pub struct RawOccupiedEntryMut<'a, K, V> {
+pub struct RawOccupiedEntryMut<'a, K, V, M = NoMix> {
     elem: Bucket<(K, V)>,
     table: &'a mut RawTable<(K, V)>,
+    mixer: &'a M,
 }
 
-unsafe impl<K, V> Send for RawOccupiedEntryMut<'_, K, V>
+unsafe impl<K, V, M> Send for RawOccupiedEntryMut<'_, K, V, M>
 where
     K: Send,
     V: Send,
+    M: Sync,
 {
 }
-unsafe impl<K, V> Sync for RawOccupiedEntryMut<'_, K, V>
+unsafe impl<K, V, M> Sync for RawOccupiedEntryMut<'_, K, V, M>
 where
     K: Sync,
     V: Sync,
+    M: Sync,
 {
 }
 
@@ -1395,8 +1767,9 @@ where
 /// It is part of the [`RawEntryMut`] enum.
 ///
 /// [`RawEntryMut`]: enum.RawEntryMut.html
-pub struct RawVacantEntryMut<'a, K, V> {
+pub struct RawVacantEntryMut<'a, K, V, M = NoMix> {
     table: &'a mut RawTable<(K, V)>,
+    mixer: &'a M,
 }
 
 /// A builder for computing where in a [`HashMap`] a key-value pair would be stored.
@@ -1404,45 +1777,50 @@ pub struct RawVacantEntryMut<'a, K, V> {
 /// See the [`HashMap::raw_entry`] docs for usage examples.
 ///
 /// [`HashMap::raw_entry`]: struct.HashMap.html#method.raw_entry
-pub struct RawEntryBuilder<'a, K, V> {
-    map: &'a AutoHashMap<K, V>,
+pub struct RawEntryBuilder<'a, K, V, M = NoMix> {
+    map: &'a AutoHashMap<K, V, M>,
 }
 
-impl<'a, K, V> RawEntryBuilderMut<'a, K, V> {
+impl<'a, K, V, M: HashMixer> RawEntryBuilderMut<'a, K, V, M> {
     /// Creates a `RawEntryMut` from the given key.
     #[cfg_attr(feature = "inline-more", inline)]
     #[allow(clippy::wrong_self_convention)]
-    pub fn from_key<Q: ?Sized>(self, k: &Q) -> RawEntryMut<'a, K, V>
+    pub fn from_key<Q: ?Sized>(self, k: &Q) -> RawEntryMut<'a, K, V, M>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         self.from_key_hashed_nocheck(k.get_hash(), k)
     }
 
     /// Creates a `RawEntryMut` from the given key and its hash.
+    ///
+    /// The hash is the key's own `AutoHash` value, which will be
+    /// post-processed by the map's mixer.
     #[inline]
     #[allow(clippy::wrong_self_convention)]
-    pub fn from_key_hashed_nocheck<Q: ?Sized>(self, hash: u64, k: &Q) -> RawEntryMut<'a, K, V>
+    pub fn from_key_hashed_nocheck<Q: ?Sized>(self, hash: u64, k: &Q) -> RawEntryMut<'a, K, V, M>
     where
-        K: Borrow<Q>,
-        Q: Eq,
+        Q: Equivalent<K>,
     {
         self.from_hash(hash, equivalent(k))
     }
 
     /// Creates a `RawEntryMut` from the given hash.
+    ///
+    /// The hash is a key's own `AutoHash` value, which will be
+    /// post-processed by the map's mixer.
     #[cfg_attr(feature = "inline-more", inline)]
     #[allow(clippy::wrong_self_convention)]
-    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, K, V>
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, K, V, M>
     where
         for<'b> F: FnMut(&'b K) -> bool,
     {
+        let hash = self.map.mixer.mix(hash);
         self.search(hash, is_match)
     }
 
     #[cfg_attr(feature = "inline-more", inline)]
-    fn search<F>(self, hash: u64, mut is_match: F) -> RawEntryMut<'a, K, V>
+    fn search<F>(self, hash: u64, mut is_match: F) -> RawEntryMut<'a, K, V, M>
     where
         for<'b> F: FnMut(&'b K) -> bool,
     {
@@ -1450,33 +1828,36 @@ impl<'a, K, V> RawEntryBuilderMut<'a, K, V> {
             Some(elem) => RawEntryMut::Occupied(RawOccupiedEntryMut {
                 elem,
                 table: &mut self.map.table,
+                mixer: &self.map.mixer,
             }),
             None => RawEntryMut::Vacant(RawVacantEntryMut {
                 table: &mut self.map.table,
+                mixer: &self.map.mixer,
             }),
         }
     }
 }
 
-impl<'a, K, V> RawEntryBuilder<'a, K, V> {
+impl<'a, K, V, M: HashMixer> RawEntryBuilder<'a, K, V, M> {
     /// Access an entry by key.
     #[cfg_attr(feature = "inline-more", inline)]
     #[allow(clippy::wrong_self_convention)]
     pub fn from_key<Q: ?Sized>(self, k: &Q) -> Option<(&'a K, &'a V)>
     where
-        K: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<K>,
     {
         self.from_key_hashed_nocheck(k.get_hash(), k)
     }
 
     /// Access an entry by a key and its hash.
+    ///
+    /// The hash is the key's own `AutoHash` value, which will be
+    /// post-processed by the map's mixer.
     #[cfg_attr(feature = "inline-more", inline)]
     #[allow(clippy::wrong_self_convention)]
     pub fn from_key_hashed_nocheck<Q: ?Sized>(self, hash: u64, k: &Q) -> Option<(&'a K, &'a V)>
     where
-        K: Borrow<Q>,
-        Q: Eq,
+        Q: Equivalent<K>,
     {
         self.from_hash(hash, equivalent(k))
     }
@@ -1493,17 +1874,21 @@ impl<'a, K, V> RawEntryBuilder<'a, K, V> {
     }
 
     /// Access an entry by hash.
+    ///
+    /// The hash is a key's own `AutoHash` value, which will be
+    /// post-processed by the map's mixer.
     #[cfg_attr(feature = "inline-more", inline)]
     #[allow(clippy::wrong_self_convention)]
     pub fn from_hash<F>(self, hash: u64, is_match: F) -> Option<(&'a K, &'a V)>
     where
         F: FnMut(&K) -> bool,
     {
+        let hash = self.map.mixer.mix(hash);
         self.search(hash, is_match)
     }
 }
 
-impl<'a, K, V> RawEntryMut<'a, K, V> {
+impl<'a, K, V, M> RawEntryMut<'a, K, V, M> {
     /// Sets the value of the entry, and returns a RawOccupiedEntryMut.
     ///
     /// # Examples
@@ -1517,9 +1902,10 @@ impl<'a, K, V> RawEntryMut<'a, K, V> {
     /// assert_eq!(entry.remove_entry(), ("horseyland", 37));
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn insert(self, key: K, value: V) -> RawOccupiedEntryMut<'a, K, V>
+    pub fn insert(self, key: K, value: V) -> RawOccupiedEntryMut<'a, K, V, M>
     where
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             RawEntryMut::Occupied(mut entry) => {
@@ -1550,6 +1936,7 @@ impl<'a, K, V> RawEntryMut<'a, K, V> {
     pub fn or_insert(self, default_key: K, default_val: V) -> (&'a mut K, &'a mut V)
     where
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             RawEntryMut::Occupied(entry) => entry.into_key_value(),
@@ -1578,6 +1965,7 @@ impl<'a, K, V> RawEntryMut<'a, K, V> {
     where
         F: FnOnce() -> (K, V),
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             RawEntryMut::Occupied(entry) => entry.into_key_value(),
@@ -1694,7 +2082,7 @@ impl<'a, K, V> RawEntryMut<'a, K, V> {
     }
 }
 
-impl<'a, K, V> RawOccupiedEntryMut<'a, K, V> {
+impl<'a, K, V, M> RawOccupiedEntryMut<'a, K, V, M> {
     /// Gets a reference to the key in the entry.
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn key(&self) -> &K {
@@ -1789,7 +2177,7 @@ impl<'a, K, V> RawOccupiedEntryMut<'a, K, V> {
     /// the entry and allows to replace or remove it based on the
     /// value of the returned option.
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn replace_entry_with<F>(self, f: F) -> RawEntryMut<'a, K, V>
+    pub fn replace_entry_with<F>(self, f: F) -> RawEntryMut<'a, K, V, M>
     where
         F: FnOnce(&K, V) -> Option<V>,
     {
@@ -1805,35 +2193,44 @@ impl<'a, K, V> RawOccupiedEntryMut<'a, K, V> {
             } else {
                 RawEntryMut::Vacant(RawVacantEntryMut {
                     table: self.table,
+                    mixer: self.mixer,
                 })
             }
         }
     }
 }
 
-impl<'a, K, V> RawVacantEntryMut<'a, K, V> {
+impl<'a, K, V, M> RawVacantEntryMut<'a, K, V, M> {
     /// Sets the value of the entry with the VacantEntry's key,
     /// and returns a mutable reference to it.
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn insert(self, key: K, value: V) -> (&'a mut K, &'a mut V)
     where
         K: AutoHash,
+        M: HashMixer,
     {
         self.insert_hashed_nocheck(key.get_hash(), key, value)
     }
 
     /// Sets the value of the entry with the VacantEntry's key,
     /// and returns a mutable reference to it.
+    ///
+    /// The hash is the key's own `AutoHash` value, which will be
+    /// post-processed by the map's mixer.
     #[cfg_attr(feature = "inline-more", inline)]
     #[allow(clippy::shadow_unrelated)]
     pub fn insert_hashed_nocheck(self, hash: u64, key: K, value: V) -> (&'a mut K, &'a mut V)
     where
         K: AutoHash,
+        M: HashMixer,
     {
         self.insert_with_hasher(hash, key, value, K::get_hash)
     }
 
     /// Set the value of an entry with a custom hasher function.
+    ///
+    /// Both `hash` and the results of `hasher` are treated as the keys' own
+    /// `AutoHash` values, which will be post-processed by the map's mixer.
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn insert_with_hasher<H>(
         self,
@@ -1844,33 +2241,40 @@ impl<'a, K, V> RawVacantEntryMut<'a, K, V> {
     ) -> (&'a mut K, &'a mut V)
     where
         H: Fn(&K) -> u64,
+        M: HashMixer,
     {
-        let &mut (ref mut k, ref mut v) = self
-            .table
-            .insert_entry(hash, (key, value), |x| hasher(&x.0));
+        let mixer = self.mixer;
+        let &mut (ref mut k, ref mut v) =
+            self.table
+                .insert_entry(mixer.mix(hash), (key, value), |x| mixer.mix(hasher(&x.0)));
         (k, v)
     }
 
     #[cfg_attr(feature = "inline-more", inline)]
-    fn insert_entry(self, key: K, value: V) -> RawOccupiedEntryMut<'a, K, V>
+    fn insert_entry(self, key: K, value: V) -> RawOccupiedEntryMut<'a, K, V, M>
     where
         K: AutoHash,
+        M: HashMixer,
     {
-        let elem = self.table.insert(key.get_hash(), (key, value), entry_hash);
+        let hash = make_hash(self.mixer, &key);
+        let elem = self
+            .table
+            .insert(hash, (key, value), make_hasher(self.mixer));
         RawOccupiedEntryMut {
             elem,
             table: self.table,
+            mixer: self.mixer,
         }
     }
 }
 
-impl<K, V> Debug for RawEntryBuilderMut<'_, K, V> {
+impl<K, V, M> Debug for RawEntryBuilderMut<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawEntryBuilder").finish()
     }
 }
 
-impl<K: Debug, V: Debug> Debug for RawEntryMut<'_, K, V> {
+impl<K: Debug, V: Debug, M> Debug for RawEntryMut<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             RawEntryMut::Vacant(ref v) => f.debug_tuple("RawEntry").field(v).finish(),
@@ -1879,7 +2283,7 @@ impl<K: Debug, V: Debug> Debug for RawEntryMut<'_, K, V> {
     }
 }
 
-impl<K: Debug, V: Debug> Debug for RawOccupiedEntryMut<'_, K, V> {
+impl<K: Debug, V: Debug, M> Debug for RawOccupiedEntryMut<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawOccupiedEntryMut")
             .field("key", self.key())
@@ -1888,13 +2292,13 @@ impl<K: Debug, V: Debug> Debug for RawOccupiedEntryMut<'_, K, V> {
     }
 }
 
-impl<K, V> Debug for RawVacantEntryMut<'_, K, V> {
+impl<K, V, M> Debug for RawVacantEntryMut<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawVacantEntryMut").finish()
     }
 }
 
-impl<K, V> Debug for RawEntryBuilder<'_, K, V> {
+impl<K, V, M> Debug for RawEntryBuilder<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawEntryBuilder").finish()
     }
@@ -1906,15 +2310,15 @@ impl<K, V> Debug for RawEntryBuilder<'_, K, V> {
 ///
 /// [`HashMap`]: struct.HashMap.html
 /// [`entry`]: struct.HashMap.html#method.entry
-pub enum Entry<'a, K, V> {
+pub enum Entry<'a, K, V, M = NoMix> {
     /// An occupied entry.
-    Occupied(OccupiedEntry<'a, K, V>),
+    Occupied(OccupiedEntry<'a, K, V, M>),
 
     /// A vacant entry.
-    Vacant(VacantEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V, M>),
 }
 
-impl<K: Debug, V: Debug> Debug for Entry<'_, K, V> {
+impl<K: Debug, V: Debug, M> Debug for Entry<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Entry::Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
@@ -1927,17 +2331,29 @@ impl<K: Debug, V: Debug> Debug for Entry<'_, K, V> {
 /// It is part of the [`Entry`] enum.
 ///
 /// [`Entry`]: enum.Entry.html
-pub struct OccupiedEntry<'a, K, V> {
+pub struct OccupiedEntry<'a, K, V, M = NoMix> {
     hash: u64,
     key: Option<K>,
     elem: Bucket<(K, V)>,
-    table: &'a mut AutoHashMap<K, V>,
+    table: &'a mut AutoHashMap<K, V, M>,
 }
 
-unsafe impl<K: Send, V: Send> Send for OccupiedEntry<'_, K, V> {}
-unsafe impl<K: Sync, V: Sync> Sync for OccupiedEntry<'_, K, V> {}
+unsafe impl<K, V, M> Send for OccupiedEntry<'_, K, V, M>
+where
+    K: Send,
+    V: Send,
+    M: Send,
+{
+}
+unsafe impl<K, V, M> Sync for OccupiedEntry<'_, K, V, M>
+where
+    K: Sync,
+    V: Sync,
+    M: Sync,
+{
+}
 
-impl<K: Debug, V: Debug> Debug for OccupiedEntry<'_, K, V> {
+impl<K: Debug, V: Debug, M> Debug for OccupiedEntry<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("OccupiedEntry")
             .field("key", self.key())
@@ -1950,19 +2366,19 @@ impl<K: Debug, V: Debug> Debug for OccupiedEntry<'_, K, V> {
 /// It is part of the [`Entry`] enum.
 ///
 /// [`Entry`]: enum.Entry.html
-pub struct VacantEntry<'a, K, V> {
+pub struct VacantEntry<'a, K, V, M = NoMix> {
     hash: u64,
     key: K,
-    table: &'a mut AutoHashMap<K, V>,
+    table: &'a mut AutoHashMap<K, V, M>,
 }
 
-impl<K: Debug, V> Debug for VacantEntry<'_, K, V> {
+impl<K: Debug, V, M> Debug for VacantEntry<'_, K, V, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("VacantEntry").field(self.key()).finish()
     }
 }
 
-impl<'a, K, V> IntoIterator for &'a AutoHashMap<K, V> {
+impl<'a, K, V, M> IntoIterator for &'a AutoHashMap<K, V, M> {
     type Item = (&'a K, &'a V);
     type IntoIter = Iter<'a, K, V>;
 
@@ -1972,7 +2388,7 @@ impl<'a, K, V> IntoIterator for &'a AutoHashMap<K, V> {
     }
 }
 
-impl<'a, K, V> IntoIterator for &'a mut AutoHashMap<K, V> {
+impl<'a, K, V, M> IntoIterator for &'a mut AutoHashMap<K, V, M> {
     type Item = (&'a K, &'a mut V);
     type IntoIter = IterMut<'a, K, V>;
 
@@ -1982,7 +2398,7 @@ impl<'a, K, V> IntoIterator for &'a mut AutoHashMap<K, V> {
     }
 }
 
-impl<K, V> IntoIterator for AutoHashMap<K, V> {
+impl<K, V, M> IntoIterator for AutoHashMap<K, V, M> {
     type Item = (K, V);
     type IntoIter = IntoIter<K, V>;
 
@@ -2214,7 +2630,7 @@ where
     }
 }
 
-impl<'a, K, V> Entry<'a, K, V> {
+impl<'a, K, V, M> Entry<'a, K, V, M> {
     /// Sets the value of the entry, and returns an OccupiedEntry.
     ///
     /// # Examples
@@ -2228,9 +2644,10 @@ impl<'a, K, V> Entry<'a, K, V> {
     /// assert_eq!(entry.key(), &"horseyland");
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn insert(self, value: V) -> OccupiedEntry<'a, K, V>
+    pub fn insert(self, value: V) -> OccupiedEntry<'a, K, V, M>
     where
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             Entry::Occupied(mut entry) => {
@@ -2261,6 +2678,7 @@ impl<'a, K, V> Entry<'a, K, V> {
     pub fn or_insert(self, default: V) -> &'a mut V
     where
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             Entry::Occupied(entry) => entry.into_mut(),
@@ -2287,6 +2705,7 @@ impl<'a, K, V> Entry<'a, K, V> {
     pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V
     where
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             Entry::Occupied(entry) => entry.into_mut(),
@@ -2313,6 +2732,7 @@ impl<'a, K, V> Entry<'a, K, V> {
     pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V
     where
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             Entry::Occupied(entry) => entry.into_mut(),
@@ -2441,7 +2861,7 @@ impl<'a, K, V> Entry<'a, K, V> {
     }
 }
 
-impl<'a, K, V: Default> Entry<'a, K, V> {
+impl<'a, K, V: Default, M> Entry<'a, K, V, M> {
     /// Ensures a value is in the entry by inserting the default value if empty,
     /// and returns a mutable reference to the value in the entry.
     ///
@@ -2459,6 +2879,7 @@ impl<'a, K, V: Default> Entry<'a, K, V> {
     pub fn or_default(self) -> &'a mut V
     where
         K: AutoHash,
+        M: HashMixer,
     {
         match self {
             Entry::Occupied(entry) => entry.into_mut(),
@@ -2467,7 +2888,7 @@ impl<'a, K, V: Default> Entry<'a, K, V> {
     }
 }
 
-impl<'a, K, V> OccupiedEntry<'a, K, V> {
+impl<'a, K, V, M> OccupiedEntry<'a, K, V, M> {
     /// Gets a reference to the key in the entry.
     ///
     /// # Examples
@@ -2742,7 +3163,7 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
     /// assert!(!map.contains_key("poneyland"));
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn replace_entry_with<F>(self, f: F) -> Entry<'a, K, V>
+    pub fn replace_entry_with<F>(self, f: F) -> Entry<'a, K, V, M>
     where
         F: FnOnce(&K, V) -> Option<V>,
     {
@@ -2773,7 +3194,7 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
     }
 }
 
-impl<'a, K, V> VacantEntry<'a, K, V> {
+impl<'a, K, V, M> VacantEntry<'a, K, V, M> {
     /// Gets a reference to the key that would be used when inserting a value
     /// through the `VacantEntry`.
     ///
@@ -2829,22 +3250,24 @@ impl<'a, K, V> VacantEntry<'a, K, V> {
     pub fn insert(self, value: V) -> &'a mut V
     where
         K: AutoHash,
+        M: HashMixer,
     {
         let table = &mut self.table.table;
-        let entry = table.insert_entry(
-            self.hash,
-            (self.key, value),
-            entry_hash,
-        );
+        let entry =
+            table.insert_entry(self.hash, (self.key, value), make_hasher(&self.table.mixer));
         &mut entry.1
     }
 
     #[cfg_attr(feature = "inline-more", inline)]
-    fn insert_entry(self, value: V) -> OccupiedEntry<'a, K, V>
+    fn insert_entry(self, value: V) -> OccupiedEntry<'a, K, V, M>
     where
         K: AutoHash,
+        M: HashMixer,
     {
-        let elem = self.table.table.insert(self.hash, (self.key, value), entry_hash);
+        let elem =
+            self.table
+                .table
+                .insert(self.hash, (self.key, value), make_hasher(&self.table.mixer));
         OccupiedEntry {
             hash: self.hash,
             key: None,
@@ -2854,14 +3277,15 @@ impl<'a, K, V> VacantEntry<'a, K, V> {
     }
 }
 
-impl<K, V> FromIterator<(K, V)> for AutoHashMap<K, V>
+impl<K, V, M> FromIterator<(K, V)> for AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash,
+    M: HashMixer + Default,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
         let iter = iter.into_iter();
-        let mut map = Self::with_capacity(iter.size_hint().0);
+        let mut map = Self::with_capacity_and_mixer(iter.size_hint().0, M::default());
         iter.for_each(|(k, v)| {
             map.insert(k, v);
         });
@@ -2871,9 +3295,10 @@ where
 
 /// Inserts all new key-values from the iterator and replaces values with existing
 /// keys with new values returned from the iterator.
-impl<K, V> Extend<(K, V)> for AutoHashMap<K, V>
+impl<K, V, M> Extend<(K, V)> for AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash,
+    M: HashMixer,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
@@ -2915,10 +3340,11 @@ where
     }
 }
 
-impl<'a, K, V> Extend<(&'a K, &'a V)> for AutoHashMap<K, V>
+impl<'a, K, V, M> Extend<(&'a K, &'a V)> for AutoHashMap<K, V, M>
 where
     K: Eq + AutoHash + Copy,
     V: Copy,
+    M: HashMixer,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
@@ -0,0 +1,267 @@
+//! Functions for mixing hash values.
+//!
+//! These are used by the built-in `AutoHash` implementations for primitives,
+//! tuples, and arrays, and by `#[derive(AutoHash)]` to combine the hashes of all
+//! fields. They may also be used directly by manual `AutoHash` implementations.
+//!
+//! The [`HashMixer`] types are applied by maps and sets to every hash they see,
+//! which can improve the distribution of weak hashes, or key the table layout
+//! with a secret seed using [`KeyedMix`].
+
+use core::fmt;
+
+/// Combines a running hash `seed` with the next `hash` value.
+///
+/// This is the 64-bit variant of Boost's `hash_combine`. It is order-dependent,
+/// so `combine(combine(0, a), b)` is generally different from
+/// `combine(combine(0, b), a)`.
+///
+/// The output is a fixed function of its inputs, and will not change between
+/// releases without a semver-breaking version bump.
+///
+/// # Examples
+///
+/// ```
+/// use autohash::mix::combine;
+///
+/// let ab = combine(combine(0, 1), 2);
+/// let ba = combine(combine(0, 2), 1);
+/// assert_ne!(ab, ba);
+/// ```
+#[inline]
+pub const fn combine(seed: u64, hash: u64) -> u64 {
+    seed ^ hash
+        .wrapping_add(0x9e37_79b9_7f4a_7c15)
+        .wrapping_add(seed << 6)
+        .wrapping_add(seed >> 2)
+}
+
+/// Mixes the bits of `x`, so every input bit affects every output bit.
+///
+/// This is the 64-bit finalizer from MurmurHash3, which is a bijection with
+/// good avalanche behavior. It's used by the `AutoHash` implementations for
+/// primitive integers, so even sequential values spread across both the low
+/// bits that choose a bucket and the high bits that `hashbrown` uses for its
+/// control bytes.
+///
+/// The output is a fixed function of its input, and will not change between
+/// releases without a semver-breaking version bump.
+///
+/// # Examples
+///
+/// ```
+/// use autohash::mix::mix64;
+///
+/// assert_eq!(mix64(0), 0);
+/// assert_ne!(mix64(1) >> 57, mix64(2) >> 57);
+/// ```
+#[inline]
+pub const fn mix64(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    x ^= x >> 33;
+    x
+}
+
+/// Folds a 128-bit hash down to 64 bits.
+///
+/// This simply XORs the two halves, which is appropriate when the input is
+/// already well distributed, like a cryptographic digest. It is the usual way
+/// to implement `AutoHash` for a type that implements `AutoHash128`.
+///
+/// # Examples
+///
+/// ```
+/// use autohash::mix::fold128;
+///
+/// assert_eq!(fold128(0x1234_0000_0000_0000_0000_0000_0000_5678), 0x1234_0000_0000_5678);
+/// ```
+#[inline]
+pub const fn fold128(hash: u128) -> u64 {
+    hash as u64 ^ (hash >> 64) as u64
+}
+
+/// A policy for mixing every hash before it is used by a table.
+///
+/// `AutoHashMap<K, V, M>` and `AutoHashSet<T, M>` apply `M::mix` to each key's
+/// `get_hash()` value, for both lookups and rehashing. This allows keys with a
+/// weakly-distributed self-hash, like sequential IDs in a `U64Hash`, to be used
+/// without re-wrapping every key.
+///
+/// The mixer must be deterministic for the lifetime of the table, so that a key
+/// always gets the same mixed hash.
+pub trait HashMixer {
+    /// Mix a key's hash into the value used by the table.
+    fn mix(&self, hash: u64) -> u64;
+}
+
+/// The identity mixer, which uses every hash exactly as given.
+///
+/// This is the default for `AutoHashMap` and `AutoHashSet`, and is appropriate
+/// when keys already have well-distributed hashes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoMix;
+
+impl HashMixer for NoMix {
+    #[inline]
+    fn mix(&self, hash: u64) -> u64 {
+        hash
+    }
+}
+
+/// A Fibonacci multiply-shift mixer.
+///
+/// This multiplies by 2<sup>64</sup>/φ, which spreads the input into the high
+/// bits, then folds the high half back into the low half for bucket selection.
+/// It is cheaper than [`Murmur3Mix`], but a weaker mix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FibonacciMix;
+
+impl HashMixer for FibonacciMix {
+    #[inline]
+    fn mix(&self, hash: u64) -> u64 {
+        let x = hash.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        x ^ (x >> 32)
+    }
+}
+
+/// A mixer using the MurmurHash3 finalizer, [`mix64`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Murmur3Mix;
+
+impl HashMixer for Murmur3Mix {
+    #[inline]
+    fn mix(&self, hash: u64) -> u64 {
+        mix64(hash)
+    }
+}
+
+/// A mixer that XORs each hash with a seed before applying [`mix64`].
+///
+/// Different seeds produce unrelated table layouts for the same keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SeededMix {
+    seed: u64,
+}
+
+impl SeededMix {
+    /// Creates a mixer with the given seed.
+    #[inline]
+    pub const fn new(seed: u64) -> Self {
+        SeededMix { seed }
+    }
+
+    /// Returns the seed of this mixer.
+    #[inline]
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl HashMixer for SeededMix {
+    #[inline]
+    fn mix(&self, hash: u64) -> u64 {
+        mix64(hash ^ self.seed)
+    }
+}
+
+/// A keyed mixer for tables that may see adversarial hashes.
+///
+/// Keys like `wrappers::RawHashed` or `U64Hash` may come directly from
+/// untrusted input, so an attacker could choose hashes that all land in the
+/// same few buckets. `KeyedMix` passes every hash through a multiply-fold with
+/// two secret 64-bit keys, followed by [`mix64`], so that bucket positions
+/// cannot be predicted without knowing the keys.
+///
+/// This only protects against flooding when the seed is kept secret. Keys with
+/// *identical* hashes will still collide with any mixer.
+///
+/// The `Debug` output does not reveal the keys.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct KeyedMix {
+    k0: u64,
+    k1: u64,
+}
+
+impl KeyedMix {
+    /// Creates a mixer with keys derived from the given seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::mix::{HashMixer, KeyedMix};
+    ///
+    /// let a = KeyedMix::new(1);
+    /// let b = KeyedMix::new(2);
+    /// assert_eq!(a.mix(42), KeyedMix::new(1).mix(42));
+    /// assert_ne!(a.mix(42), b.mix(42));
+    /// ```
+    #[inline]
+    pub const fn new(seed: u64) -> Self {
+        KeyedMix {
+            k0: mix64(seed ^ 0x243f_6a88_85a3_08d3),
+            // The multiplier must be odd, so it can't erase the input.
+            k1: mix64(seed ^ 0x1319_8a2e_0370_7344) | 1,
+        }
+    }
+
+    /// Creates a mixer with keys from the operating system's random source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if random data could not be read from [`getrandom`].
+    ///
+    /// [`getrandom`]: https://docs.rs/getrandom
+    #[cfg(feature = "getrandom")]
+    pub fn random() -> Self {
+        let mut bytes = [0u8; 16];
+        if let Err(error) = getrandom::getrandom(&mut bytes) {
+            panic!("failed to get a random seed: {}", error);
+        }
+        let mut k0 = [0u8; 8];
+        let mut k1 = [0u8; 8];
+        k0.copy_from_slice(&bytes[..8]);
+        k1.copy_from_slice(&bytes[8..]);
+        KeyedMix {
+            k0: u64::from_ne_bytes(k0),
+            k1: u64::from_ne_bytes(k1) | 1,
+        }
+    }
+}
+
+/// Creates a randomly keyed mixer, like [`KeyedMix::random`].
+#[cfg(feature = "getrandom")]
+impl Default for KeyedMix {
+    #[inline]
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+impl fmt::Debug for KeyedMix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyedMix { .. }")
+    }
+}
+
+impl HashMixer for KeyedMix {
+    #[inline]
+    fn mix(&self, hash: u64) -> u64 {
+        mix64(folded_multiply(hash ^ self.k0, self.k1))
+    }
+}
+
+/// Multiplies to a 128-bit product, and folds the halves together with XOR.
+#[inline]
+const fn folded_multiply(x: u64, y: u64) -> u64 {
+    fold128(x as u128 * y as u128)
+}
+
+impl<M: HashMixer + ?Sized> HashMixer for &'_ M {
+    #[inline]
+    fn mix(&self, hash: u64) -> u64 {
+        M::mix(*self, hash)
+    }
+}
@@ -0,0 +1,807 @@
+//! A persistent map with structural sharing between versions.
+//!
+//! [`AutoHamt`] is a hash array mapped trie, which uses each key's own hash as
+//! the path from the root: every level of the trie consumes 5 bits of the
+//! mixed hash to choose one of up to 32 children. Nodes are shared with `Arc`,
+//! so [`insert`] and [`remove`] return a new version of the map that only
+//! copies the O(log<sub>32</sub> n) nodes along one path, and every other node
+//! is shared with the old version. Cloning a version is O(1).
+//!
+//! Keys whose mixed hashes are fully identical are kept together in a list at
+//! the bottom of the trie, where they are compared with `Eq`.
+//!
+//! Each entry is stored in its own `Arc` along with its hash, so neither the
+//! keys nor the values need to implement `Clone`, and no key is hashed again
+//! when the trie is restructured.
+//!
+//! [`AutoHamt`]: struct.AutoHamt.html
+//! [`insert`]: struct.AutoHamt.html#method.insert
+//! [`remove`]: struct.AutoHamt.html#method.remove
+
+use crate::map::AutoHashMap;
+use crate::mix::{HashMixer, NoMix};
+use crate::{AutoHash, Equivalent};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::Index;
+use core::slice;
+
+/// The number of hash bits consumed by each level of the trie.
+const BITS: u32 = 5;
+
+/// A persistent hash map, implemented as a hash array mapped trie.
+///
+/// See the [module documentation] for details.
+///
+/// [module documentation]: index.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::persistent::AutoHamt;
+///
+/// let v1 = AutoHamt::new().insert(1, "a").insert(2, "b");
+/// let v2 = v1.insert(3, "c").remove(&1);
+///
+/// // The old version is unchanged.
+/// assert_eq!(v1.len(), 2);
+/// assert_eq!(v1.get(&1), Some(&"a"));
+/// assert_eq!(v1.get(&3), None);
+///
+/// assert_eq!(v2.len(), 2);
+/// assert_eq!(v2.get(&1), None);
+/// assert_eq!(v2.get(&3), Some(&"c"));
+/// ```
+pub struct AutoHamt<K, V, M = NoMix> {
+    mixer: M,
+    root: Arc<Node<K, V>>,
+    len: usize,
+}
+
+/// An interior node of the trie.
+enum Node<K, V> {
+    /// Up to 32 entries, present where their bit is set in the bitmap.
+    Branch {
+        bitmap: u32,
+        entries: Vec<Entry<K, V>>,
+    },
+    /// Leaves whose mixed hashes are all identical.
+    Collision(Vec<Entry<K, V>>),
+}
+
+/// A slot in a node, which is either a single key-value pair or a child.
+enum Entry<K, V> {
+    Leaf(u64, Arc<(K, V)>),
+    Child(Arc<Node<K, V>>),
+}
+
+/// The result of removing a key from a node.
+enum Removal<K, V> {
+    /// The key was not found, so the node is unchanged.
+    Missing,
+    /// The node has no entries left.
+    Empty,
+    /// The node has a single leaf left, which can replace it in the parent.
+    Collapse(Entry<K, V>),
+    /// The node was changed.
+    Replace(Node<K, V>),
+}
+
+impl<K, V> Clone for Entry<K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            Entry::Leaf(hash, pair) => Entry::Leaf(*hash, Arc::clone(pair)),
+            Entry::Child(node) => Entry::Child(Arc::clone(node)),
+        }
+    }
+}
+
+/// Returns the bit in a branch's bitmap for `hash` at the given `shift`.
+#[inline]
+fn bit(hash: u64, shift: u32) -> u32 {
+    1 << ((hash >> shift) & 0x1f)
+}
+
+/// Returns the position in a branch's entries for a bit in its bitmap.
+#[inline]
+fn position(bitmap: u32, bit: u32) -> usize {
+    (bitmap & (bit - 1)).count_ones() as usize
+}
+
+impl<K, V> Node<K, V> {
+    #[inline]
+    fn empty() -> Self {
+        Node::Branch {
+            bitmap: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn entries(&self) -> &[Entry<K, V>] {
+        match self {
+            Node::Branch { entries, .. } | Node::Collision(entries) => entries,
+        }
+    }
+
+    /// Creates a node at `shift` holding two leaves with different keys.
+    fn pair(shift: u32, a: Entry<K, V>, a_hash: u64, b: Entry<K, V>, b_hash: u64) -> Self {
+        if shift >= 64 {
+            return Node::Collision(vec![a, b]);
+        }
+        let (a_bit, b_bit) = (bit(a_hash, shift), bit(b_hash, shift));
+        let entries = if a_bit == b_bit {
+            let child = Node::pair(shift + BITS, a, a_hash, b, b_hash);
+            vec![Entry::Child(Arc::new(child))]
+        } else if a_bit < b_bit {
+            vec![a, b]
+        } else {
+            vec![b, a]
+        };
+        Node::Branch {
+            bitmap: a_bit | b_bit,
+            entries,
+        }
+    }
+
+    fn get<Q: ?Sized>(&self, hash: u64, k: &Q) -> Option<&(K, V)>
+    where
+        Q: Equivalent<K>,
+    {
+        let mut node = self;
+        let mut shift = 0;
+        loop {
+            let entry = match node {
+                Node::Branch { bitmap, entries } => {
+                    let bit = bit(hash, shift);
+                    if bitmap & bit == 0 {
+                        return None;
+                    }
+                    &entries[position(*bitmap, bit)]
+                }
+                Node::Collision(entries) => {
+                    return entries.iter().find_map(|entry| match entry {
+                        Entry::Leaf(_, pair) if k.equivalent(&pair.0) => Some(&**pair),
+                        _ => None,
+                    });
+                }
+            };
+            match entry {
+                Entry::Leaf(h, pair) => {
+                    return if *h == hash && k.equivalent(&pair.0) {
+                        Some(pair)
+                    } else {
+                        None
+                    };
+                }
+                Entry::Child(child) => {
+                    node = child;
+                    shift += BITS;
+                }
+            }
+        }
+    }
+
+    /// Returns a copy of this node with the leaf inserted, and whether the
+    /// leaf was added rather than replacing an equal key.
+    fn insert(&self, shift: u32, hash: u64, pair: Arc<(K, V)>) -> (Self, bool)
+    where
+        K: Eq,
+    {
+        match self {
+            Node::Branch { bitmap, entries } => {
+                let bit = bit(hash, shift);
+                let pos = position(*bitmap, bit);
+                let mut entries = entries.clone();
+                if bitmap & bit == 0 {
+                    entries.insert(pos, Entry::Leaf(hash, pair));
+                    let bitmap = bitmap | bit;
+                    return (Node::Branch { bitmap, entries }, true);
+                }
+                let added = match &entries[pos] {
+                    Entry::Leaf(h, old) if *h == hash && old.0 == pair.0 => {
+                        entries[pos] = Entry::Leaf(hash, pair);
+                        false
+                    }
+                    Entry::Leaf(h, _) => {
+                        let h = *h;
+                        let old = entries[pos].clone();
+                        let new = Entry::Leaf(hash, pair);
+                        let child = Node::pair(shift + BITS, old, h, new, hash);
+                        entries[pos] = Entry::Child(Arc::new(child));
+                        true
+                    }
+                    Entry::Child(child) => {
+                        let (child, added) = child.insert(shift + BITS, hash, pair);
+                        entries[pos] = Entry::Child(Arc::new(child));
+                        added
+                    }
+                };
+                let bitmap = *bitmap;
+                (Node::Branch { bitmap, entries }, added)
+            }
+            Node::Collision(entries) => {
+                let mut entries = entries.clone();
+                let found = entries.iter().position(|entry| match entry {
+                    Entry::Leaf(_, old) => old.0 == pair.0,
+                    Entry::Child(_) => false,
+                });
+                let added = match found {
+                    Some(i) => {
+                        entries[i] = Entry::Leaf(hash, pair);
+                        false
+                    }
+                    None => {
+                        entries.push(Entry::Leaf(hash, pair));
+                        true
+                    }
+                };
+                (Node::Collision(entries), added)
+            }
+        }
+    }
+
+    fn remove<Q: ?Sized>(&self, shift: u32, hash: u64, k: &Q) -> Removal<K, V>
+    where
+        Q: Equivalent<K>,
+    {
+        match self {
+            Node::Branch { bitmap, entries } => {
+                let bit = bit(hash, shift);
+                if bitmap & bit == 0 {
+                    return Removal::Missing;
+                }
+                let pos = position(*bitmap, bit);
+                let replacement = match &entries[pos] {
+                    Entry::Leaf(h, pair) => {
+                        if *h == hash && k.equivalent(&pair.0) {
+                            None
+                        } else {
+                            return Removal::Missing;
+                        }
+                    }
+                    Entry::Child(child) => match child.remove(shift + BITS, hash, k) {
+                        Removal::Missing => return Removal::Missing,
+                        Removal::Empty => None,
+                        Removal::Collapse(entry) => Some(entry),
+                        Removal::Replace(child) => Some(Entry::Child(Arc::new(child))),
+                    },
+                };
+                let mut entries = entries.clone();
+                let mut bitmap = *bitmap;
+                match replacement {
+                    Some(entry) => entries[pos] = entry,
+                    None => {
+                        entries.remove(pos);
+                        bitmap &= !bit;
+                    }
+                }
+                // The root is never collapsed, so it stays a branch.
+                match entries.len() {
+                    0 => Removal::Empty,
+                    1 if shift > 0 && matches!(entries[0], Entry::Leaf(..)) => {
+                        Removal::Collapse(entries.pop().unwrap())
+                    }
+                    _ => Removal::Replace(Node::Branch { bitmap, entries }),
+                }
+            }
+            Node::Collision(entries) => {
+                let found = entries.iter().position(|entry| match entry {
+                    Entry::Leaf(_, pair) => k.equivalent(&pair.0),
+                    Entry::Child(_) => false,
+                });
+                match found {
+                    None => Removal::Missing,
+                    Some(i) => {
+                        let mut entries = entries.clone();
+                        entries.remove(i);
+                        match entries.len() {
+                            0 => Removal::Empty,
+                            1 => Removal::Collapse(entries.pop().unwrap()),
+                            _ => Removal::Replace(Node::Collision(entries)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<K, V> AutoHamt<K, V, NoMix> {
+    /// Creates an empty `AutoHamt`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_mixer(NoMix)
+    }
+}
+
+impl<K, V, M> AutoHamt<K, V, M> {
+    /// Creates an empty `AutoHamt` which will use the given mixer to
+    /// post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self {
+            mixer,
+            root: Arc::new(Node::empty()),
+            len: 0,
+        }
+    }
+
+    /// Returns a reference to the map's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        &self.mixer
+    }
+
+    /// Returns the number of elements in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if both maps are the same version, or derived from it
+    /// without any changes, so they share the same root node.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.root, &other.root)
+    }
+
+    /// An iterator visiting all key-value pairs in an arbitrary order.
+    /// The iterator element type is `(&'a K, &'a V)`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            stack: vec![self.root.entries().iter()],
+            remaining: self.len,
+        }
+    }
+
+    /// An iterator visiting all keys in an arbitrary order.
+    /// The iterator element type is `&'a K`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// An iterator visiting all values in an arbitrary order.
+    /// The iterator element type is `&'a V`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+}
+
+impl<K, V, M> AutoHamt<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_key_value(k) {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_key_value<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.mixer.mix(k.get_hash());
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.root.get(hash, k) {
+            Some((key, value)) => Some((key, value)),
+            None => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        self.get_key_value(k).is_some()
+    }
+
+    /// Returns a new version of the map with the key-value pair inserted.
+    ///
+    /// If the map already had an equal key, both the key and value are
+    /// replaced in the new version.
+    pub fn insert(&self, k: K, v: V) -> Self
+    where
+        M: Clone,
+    {
+        let hash = self.mixer.mix(k.get_hash());
+        let (root, added) = self.root.insert(0, hash, Arc::new((k, v)));
+        Self {
+            mixer: self.mixer.clone(),
+            root: Arc::new(root),
+            len: self.len + added as usize,
+        }
+    }
+
+    /// Returns a new version of the map with the key removed.
+    ///
+    /// If the key is not present, this returns a clone of the map, sharing
+    /// the same root.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, but [`AutoHash`] on the query *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
+    pub fn remove<Q: ?Sized>(&self, k: &Q) -> Self
+    where
+        Q: AutoHash + Equivalent<K>,
+        M: Clone,
+    {
+        let hash = self.mixer.mix(k.get_hash());
+        let root = match self.root.remove(0, hash, k) {
+            Removal::Missing => return self.clone(),
+            Removal::Empty => Node::empty(),
+            Removal::Replace(node) => node,
+            // The root is never collapsed.
+            Removal::Collapse(_) => unreachable!(),
+        };
+        Self {
+            mixer: self.mixer.clone(),
+            root: Arc::new(root),
+            len: self.len - 1,
+        }
+    }
+
+    /// Copies the entries into a new `AutoHashMap`, using the same mixer.
+    pub fn to_map(&self) -> AutoHashMap<K, V, M>
+    where
+        K: Clone,
+        V: Clone,
+        M: Clone,
+    {
+        let mut map = AutoHashMap::with_capacity_and_mixer(self.len, self.mixer.clone());
+        map.extend(self.iter().map(|(k, v)| (k.clone(), v.clone())));
+        map
+    }
+}
+
+impl<K, V, M: Clone> Clone for AutoHamt<K, V, M> {
+    /// Returns a new handle to the same version of the map, in O(1) time.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Self {
+            mixer: self.mixer.clone(),
+            root: Arc::clone(&self.root),
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V, M> Default for AutoHamt<K, V, M>
+where
+    M: Default,
+{
+    /// Creates an empty `AutoHamt<K, V, M>`, with the `Default` value for the
+    /// mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::with_mixer(M::default())
+    }
+}
+
+impl<K, V, M> PartialEq for AutoHamt<K, V, M>
+where
+    K: Eq + AutoHash,
+    V: PartialEq,
+    M: HashMixer,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter()
+            .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K, V, M> Eq for AutoHamt<K, V, M>
+where
+    K: Eq + AutoHash,
+    V: Eq,
+    M: HashMixer,
+{
+}
+
+impl<K: Debug, V: Debug, M> Debug for AutoHamt<K, V, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, Q: ?Sized, V, M> Index<&Q> for AutoHamt<K, V, M>
+where
+    K: Eq + AutoHash,
+    Q: AutoHash + Equivalent<K>,
+    M: HashMixer,
+{
+    type Output = V;
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the `AutoHamt`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K, V, M> FromIterator<(K, V)> for AutoHamt<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer + Clone + Default,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, M> Extend<(K, V)> for AutoHamt<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer + Clone,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            *self = self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V, M> From<AutoHashMap<K, V, M>> for AutoHamt<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer + Clone,
+{
+    /// Moves the entries of an `AutoHashMap` into a new `AutoHamt`, using the
+    /// same mixer.
+    fn from(map: AutoHashMap<K, V, M>) -> Self {
+        let mut hamt = Self::with_mixer(map.mixer().clone());
+        hamt.extend(map);
+        hamt
+    }
+}
+
+impl<K, V, M> From<&AutoHamt<K, V, M>> for AutoHashMap<K, V, M>
+where
+    K: Eq + AutoHash + Clone,
+    V: Clone,
+    M: HashMixer + Clone,
+{
+    /// Copies the entries of an `AutoHamt` into a new `AutoHashMap`, like
+    /// [`AutoHamt::to_map`].
+    ///
+    /// [`AutoHamt::to_map`]: persistent/struct.AutoHamt.html#method.to_map
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn from(hamt: &AutoHamt<K, V, M>) -> Self {
+        hamt.to_map()
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a AutoHamt<K, V, M> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+/// An iterator over the entries of an `AutoHamt`.
+///
+/// This `struct` is created by the [`iter`] method on [`AutoHamt`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.AutoHamt.html#method.iter
+/// [`AutoHamt`]: struct.AutoHamt.html
+pub struct Iter<'a, K, V> {
+    stack: Vec<slice::Iter<'a, Entry<K, V>>>,
+    remaining: usize,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Iter {
+            stack: self.stack.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(Entry::Leaf(_, pair)) => {
+                    self.remaining -= 1;
+                    return Some((&pair.0, &pair.1));
+                }
+                Some(Entry::Child(child)) => self.stack.push(child.entries().iter()),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K: Debug, V: Debug> Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the keys of an `AutoHamt`.
+///
+/// This `struct` is created by the [`keys`] method on [`AutoHamt`]. See its
+/// documentation for more.
+///
+/// [`keys`]: struct.AutoHamt.html#method.keys
+/// [`AutoHamt`]: struct.AutoHamt.html
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Keys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+impl<K: Debug, V> Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values of an `AutoHamt`.
+///
+/// This `struct` is created by the [`values`] method on [`AutoHamt`]. See its
+/// documentation for more.
+///
+/// [`values`]: struct.AutoHamt.html#method.values
+/// [`AutoHamt`]: struct.AutoHamt.html
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Values {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a V> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+impl<K, V: Debug> Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
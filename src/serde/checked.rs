@@ -0,0 +1,47 @@
+//! Deserialize `RawHashed` values, checking that their hash is correct.
+//!
+//! This is meant for `#[serde(deserialize_with = "autohash::serde::checked::deserialize")]`
+//! on a `RawHashed<T>` field, where `T` also implements `AutoHash`.
+
+use serde::de::{Deserialize, Deserializer, Error};
+
+use crate::wrappers::RawHashed;
+use crate::AutoHash;
+
+/// Deserializes a `RawHashed<T>` from a `(hash, value)` tuple, returning an
+/// error if the hash is not equal to `value.get_hash()`.
+///
+/// # Errors
+///
+/// Returns an error if deserialization fails, or if the hash does not match.
+///
+/// # Examples
+///
+/// ```
+/// use autohash::serde::checked;
+/// use autohash::wrappers::RawHashed;
+/// use autohash::AutoHash;
+/// use serde::de::value::{Error, SeqDeserializer};
+///
+/// let good = SeqDeserializer::<_, Error>::new(vec![42u64.get_hash(), 42].into_iter());
+/// let raw: RawHashed<u64> = checked::deserialize(good).unwrap();
+/// assert_eq!(raw.value, 42u64);
+///
+/// let forged = SeqDeserializer::<_, Error>::new(vec![0, 42u64].into_iter());
+/// assert!(checked::deserialize::<_, u64>(forged).is_err());
+/// ```
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<RawHashed<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + AutoHash,
+{
+    let (hash, value) = <(u64, T)>::deserialize(deserializer)?;
+    let expected = value.get_hash();
+    if hash != expected {
+        return Err(D::Error::custom(format_args!(
+            "invalid hash {:#x} for a value with hash {:#x}",
+            hash, expected
+        )));
+    }
+    Ok(RawHashed::new(hash, value))
+}
@@ -0,0 +1,47 @@
+//! Serialize maps and sets in sorted hash order.
+//!
+//! This is like [`sorted`], but the entries are sorted by the keys' own
+//! `AutoHash` values first, and only compared by key when their hashes are
+//! equal. That is cheaper than [`sorted`] for keys that are expensive to
+//! compare, like long strings, and the output is still deterministic because
+//! it does not depend on the table's mixer or layout.
+//!
+//! It is meant to be used with `#[serde(with = "autohash::serde::hash_sorted")]`.
+//! Deserialization is unchanged.
+//!
+//! [`sorted`]: ../sorted/index.html
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::Serializer;
+
+use super::sorted::SerializeSorted;
+
+/// Serializes a map or set with its entries sorted by hash, then by key.
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+#[inline]
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: SerializeSorted + ?Sized,
+    S: Serializer,
+{
+    value.serialize_hash_sorted(serializer)
+}
+
+/// Deserializes a collection normally.
+///
+/// This exists so the module can be used with `#[serde(with = ...)]`.
+///
+/// # Errors
+///
+/// Returns any error from the deserializer.
+#[inline]
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer)
+}
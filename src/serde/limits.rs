@@ -0,0 +1,302 @@
+//! Deserialize maps and sets with configurable allocation limits.
+//!
+//! The default `Deserialize` implementations preallocate at most 4096 entries
+//! from the input's size hint, but otherwise grow as long as the input keeps
+//! providing entries. A [`DeserializeConfig`] sets both the preallocation cap
+//! and a maximum number of entries, and it grows the table with `try_reserve`,
+//! so an allocation failure becomes a deserialization error instead of a
+//! panic.
+//!
+//! The limits can be used through [`AutoHashMap::deserialize_with_limits`] and
+//! [`AutoHashSet::deserialize_with_limits`], or as a `DeserializeSeed` from
+//! [`DeserializeConfig::seed`].
+//!
+//! # Examples
+//!
+//! ```
+//! use autohash::serde::limits::DeserializeConfig;
+//! use autohash::AutoHashSet;
+//! use serde::de::value::{Error, SeqDeserializer};
+//!
+//! let config = DeserializeConfig::new().max_len(2);
+//!
+//! let input = SeqDeserializer::<_, Error>::new(vec![1, 2].into_iter());
+//! let set = AutoHashSet::<i32>::deserialize_with_limits(input, config).unwrap();
+//! assert_eq!(set.len(), 2);
+//!
+//! let input = SeqDeserializer::<_, Error>::new(vec![1, 2, 3].into_iter());
+//! let error = AutoHashSet::<i32>::deserialize_with_limits(input, config).unwrap_err();
+//! assert_eq!(error.to_string(), "too many entries, expected at most 2");
+//! ```
+//!
+//! [`DeserializeConfig`]: struct.DeserializeConfig.html
+//! [`DeserializeConfig::seed`]: struct.DeserializeConfig.html#method.seed
+//! [`AutoHashMap::deserialize_with_limits`]: ../../map/struct.AutoHashMap.html#method.deserialize_with_limits
+//! [`AutoHashSet::deserialize_with_limits`]: ../../set/struct.AutoHashSet.html#method.deserialize_with_limits
+
+use core::cmp;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+
+use crate::mix::HashMixer;
+use crate::{AutoHash, AutoHashMap, AutoHashSet, TryReserveError};
+
+/// The default number of entries to preallocate from a size hint.
+pub const DEFAULT_MAX_PREALLOCATE: usize = 4096;
+
+/// Limits on the memory used while deserializing a map or set.
+///
+/// By default, this preallocates up to [`DEFAULT_MAX_PREALLOCATE`] entries
+/// and has no maximum length, just like the default `Deserialize`.
+///
+/// [`DEFAULT_MAX_PREALLOCATE`]: constant.DEFAULT_MAX_PREALLOCATE.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeserializeConfig {
+    max_preallocate: usize,
+    max_len: Option<usize>,
+}
+
+impl Default for DeserializeConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeserializeConfig {
+    /// Creates a configuration with the default limits.
+    #[inline]
+    pub const fn new() -> Self {
+        DeserializeConfig {
+            max_preallocate: DEFAULT_MAX_PREALLOCATE,
+            max_len: None,
+        }
+    }
+
+    /// Sets the maximum number of entries to preallocate from the input's size
+    /// hint. The table still grows past this as entries are read.
+    #[inline]
+    pub fn max_preallocate(mut self, max_preallocate: usize) -> Self {
+        self.max_preallocate = max_preallocate;
+        self
+    }
+
+    /// Sets the maximum number of entries to read from the input.
+    ///
+    /// Repeated keys are counted each time they appear, so this also bounds
+    /// the work spent on an input full of duplicates.
+    #[inline]
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Returns a `DeserializeSeed` for a map or set using these limits.
+    #[inline]
+    pub fn seed<T>(self) -> LimitedSeed<T> {
+        LimitedSeed {
+            config: self,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries to preallocate for a size hint.
+    #[inline]
+    fn preallocate(&self, hint: Option<usize>) -> usize {
+        let max_len = self.max_len.unwrap_or_else(usize::max_value);
+        cmp::min(hint.unwrap_or(0), cmp::min(self.max_preallocate, max_len))
+    }
+
+    /// Checks that `count` entries are within the maximum length.
+    #[inline]
+    fn check_len<E: Error>(&self, count: usize) -> Result<(), E> {
+        match self.max_len {
+            Some(max_len) if count > max_len => Err(E::custom(format_args!(
+                "too many entries, expected at most {}",
+                max_len
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Converts a `try_reserve` failure into a deserialization error.
+#[inline]
+fn reserve_result<E: Error>(result: Result<(), TryReserveError>) -> Result<(), E> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(TryReserveError::CapacityOverflow) => Err(E::custom("capacity overflow")),
+        Err(TryReserveError::AllocError { .. }) => Err(E::custom("memory allocation failed")),
+    }
+}
+
+/// A `DeserializeSeed` for a map or set, created by [`DeserializeConfig::seed`].
+///
+/// [`DeserializeConfig::seed`]: struct.DeserializeConfig.html#method.seed
+#[derive(Clone, Copy, Debug)]
+pub struct LimitedSeed<T> {
+    config: DeserializeConfig,
+    marker: PhantomData<T>,
+}
+
+impl<'de, K, V, M> DeserializeSeed<'de> for LimitedSeed<AutoHashMap<K, V, M>>
+where
+    K: Deserialize<'de> + Eq + AutoHash,
+    V: Deserialize<'de>,
+    M: HashMixer + Default,
+{
+    type Value = AutoHashMap<K, V, M>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MapVisitor<K, V, M> {
+            config: DeserializeConfig,
+            marker: PhantomData<AutoHashMap<K, V, M>>,
+        }
+
+        impl<'de, K, V, M> Visitor<'de> for MapVisitor<K, V, M>
+        where
+            K: Deserialize<'de> + Eq + AutoHash,
+            V: Deserialize<'de>,
+            M: HashMixer + Default,
+        {
+            type Value = AutoHashMap<K, V, M>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut values = AutoHashMap::with_mixer(M::default());
+                reserve_result(values.try_reserve(self.config.preallocate(map.size_hint())))?;
+
+                let mut count = 0;
+                while let Some((key, value)) = map.next_entry()? {
+                    count += 1;
+                    self.config.check_len(count)?;
+                    reserve_result(values.try_reserve(1))?;
+                    values.insert(key, value);
+                }
+
+                Ok(values)
+            }
+        }
+
+        let visitor = MapVisitor {
+            config: self.config,
+            marker: PhantomData,
+        };
+        deserializer.deserialize_map(visitor)
+    }
+}
+
+impl<'de, T, M> DeserializeSeed<'de> for LimitedSeed<AutoHashSet<T, M>>
+where
+    T: Deserialize<'de> + Eq + AutoHash,
+    M: HashMixer + Default,
+{
+    type Value = AutoHashSet<T, M>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SeqVisitor<T, M> {
+            config: DeserializeConfig,
+            marker: PhantomData<AutoHashSet<T, M>>,
+        }
+
+        impl<'de, T, M> Visitor<'de> for SeqVisitor<T, M>
+        where
+            T: Deserialize<'de> + Eq + AutoHash,
+            M: HashMixer + Default,
+        {
+            type Value = AutoHashSet<T, M>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values = AutoHashSet::with_mixer(M::default());
+                reserve_result(values.try_reserve(self.config.preallocate(seq.size_hint())))?;
+
+                let mut count = 0;
+                while let Some(value) = seq.next_element()? {
+                    count += 1;
+                    self.config.check_len(count)?;
+                    reserve_result(values.try_reserve(1))?;
+                    values.insert(value);
+                }
+
+                Ok(values)
+            }
+        }
+
+        let visitor = SeqVisitor {
+            config: self.config,
+            marker: PhantomData,
+        };
+        deserializer.deserialize_seq(visitor)
+    }
+}
+
+impl<K, V, M> AutoHashMap<K, V, M> {
+    /// Deserializes a map with the given allocation limits.
+    ///
+    /// See the [`limits`] module for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails, if the input has more
+    /// entries than the configured maximum, or if memory allocation fails.
+    ///
+    /// [`limits`]: ../serde/limits/index.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn deserialize_with_limits<'de, D>(
+        deserializer: D,
+        config: DeserializeConfig,
+    ) -> Result<Self, D::Error>
+    where
+        K: Deserialize<'de> + Eq + AutoHash,
+        V: Deserialize<'de>,
+        M: HashMixer + Default,
+        D: Deserializer<'de>,
+    {
+        config.seed::<Self>().deserialize(deserializer)
+    }
+}
+
+impl<T, M> AutoHashSet<T, M> {
+    /// Deserializes a set with the given allocation limits.
+    ///
+    /// See the [`limits`] module for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails, if the input has more
+    /// entries than the configured maximum, or if memory allocation fails.
+    ///
+    /// [`limits`]: ../serde/limits/index.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn deserialize_with_limits<'de, D>(
+        deserializer: D,
+        config: DeserializeConfig,
+    ) -> Result<Self, D::Error>
+    where
+        T: Deserialize<'de> + Eq + AutoHash,
+        M: HashMixer + Default,
+        D: Deserializer<'de>,
+    {
+        config.seed::<Self>().deserialize(deserializer)
+    }
+}
@@ -0,0 +1,41 @@
+//! Serde helpers for `autohash` types.
+//!
+//! With the `serde` feature, maps, sets, and the key types in [`wrappers`]
+//! implement `Serialize` and `Deserialize` directly. The hashing wrappers like
+//! `AutoHashed` and `MemoHashed` serialize only their value, and recompute the
+//! hash when deserialized, so the hash can't be forged by the input.
+//!
+//! `RawHashed` has no way to recompute its hash, so it serializes as a
+//! `(hash, value)` tuple, and the default `Deserialize` trusts that hash. The
+//! [`checked`] module can be used instead when the value's own `AutoHash` is
+//! expected to match.
+//!
+//! Maps and sets keep the last value for a repeated key, like inserting each
+//! entry in turn. The [`strict`] module can be used to reject duplicates.
+//!
+//! Maps and sets serialize in table order, which is not deterministic. The
+//! [`sorted`] and [`hash_sorted`] modules serialize in a stable order instead.
+//!
+//! Maps serialize with `collect_map`, which some formats only allow for string
+//! keys. The [`pairs`] module serializes a map as a sequence of pairs instead.
+//!
+//! The [`limits`] module deserializes maps and sets with a configurable
+//! preallocation cap and maximum length, for input that may be hostile.
+//!
+//! `ByAddress` does not implement serde traits, since an address is not
+//! meaningful outside of the current process.
+//!
+//! [`wrappers`]: ../wrappers/index.html
+//! [`checked`]: checked/index.html
+//! [`strict`]: strict/index.html
+//! [`sorted`]: sorted/index.html
+//! [`pairs`]: pairs/index.html
+//! [`limits`]: limits/index.html
+//! [`hash_sorted`]: hash_sorted/index.html
+
+pub mod checked;
+pub mod hash_sorted;
+pub mod limits;
+pub mod pairs;
+pub mod sorted;
+pub mod strict;
@@ -0,0 +1,116 @@
+//! Serialize maps as a sequence of `(key, value)` pairs.
+//!
+//! Some formats, like JSON, only allow string keys in a map, so a map keyed by
+//! `U64Hash` or a struct can't use the default `Serialize`. This module writes
+//! the entries as a sequence of 2-tuples instead, which JSON represents as
+//! `[[key, value], ...]`, and reads them back the same way.
+//!
+//! As with the default `Deserialize`, a later duplicate key replaces the value
+//! of an earlier one.
+//!
+//! It is meant to be used with `#[serde(with = "autohash::serde::pairs")]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use autohash::serde::pairs;
+//! use autohash::wrappers::U64Hash;
+//! use autohash::AutoHashMap;
+//! use serde_test::{assert_ser_tokens, Token};
+//!
+//! struct Index(AutoHashMap<U64Hash, char>);
+//!
+//! impl serde::Serialize for Index {
+//!     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+//!         pairs::serialize(&self.0, serializer)
+//!     }
+//! }
+//!
+//! let mut map = AutoHashMap::new();
+//! map.insert(U64Hash(1), 'a');
+//! assert_ser_tokens(
+//!     &Index(map),
+//!     &[
+//!         Token::Seq { len: Some(1) },
+//!         Token::Tuple { len: 2 },
+//!         Token::U64(1),
+//!         Token::Char('a'),
+//!         Token::TupleEnd,
+//!         Token::SeqEnd,
+//!     ],
+//! );
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::external_trait_impls::serde::size_hint::cautious;
+use crate::mix::HashMixer;
+use crate::{AutoHash, AutoHashMap};
+
+/// Serializes a map as a sequence of `(key, value)` pairs.
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+#[inline]
+pub fn serialize<K, V, M, S>(map: &AutoHashMap<K, V, M>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(map.iter())
+}
+
+/// Deserializes a map from a sequence of `(key, value)` pairs.
+///
+/// # Errors
+///
+/// Returns an error if deserialization fails.
+pub fn deserialize<'de, K, V, M, D>(deserializer: D) -> Result<AutoHashMap<K, V, M>, D::Error>
+where
+    K: Deserialize<'de> + Eq + AutoHash,
+    V: Deserialize<'de>,
+    M: HashMixer + Default,
+    D: Deserializer<'de>,
+{
+    struct PairsVisitor<K, V, M> {
+        marker: PhantomData<AutoHashMap<K, V, M>>,
+    }
+
+    impl<'de, K, V, M> Visitor<'de> for PairsVisitor<K, V, M>
+    where
+        K: Deserialize<'de> + Eq + AutoHash,
+        V: Deserialize<'de>,
+        M: HashMixer + Default,
+    {
+        type Value = AutoHashMap<K, V, M>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a sequence of key-value pairs")
+        }
+
+        #[cfg_attr(feature = "inline-more", inline)]
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut values =
+                AutoHashMap::with_capacity_and_mixer(cautious(seq.size_hint()), M::default());
+
+            while let Some((key, value)) = seq.next_element()? {
+                values.insert(key, value);
+            }
+
+            Ok(values)
+        }
+    }
+
+    let visitor = PairsVisitor {
+        marker: PhantomData,
+    };
+    deserializer.deserialize_seq(visitor)
+}
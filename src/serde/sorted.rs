@@ -0,0 +1,144 @@
+//! Serialize maps and sets in sorted key order.
+//!
+//! The default `Serialize` implementations for `AutoHashMap` and
+//! `AutoHashSet` emit entries in table order, which depends on the mixer,
+//! the capacity, and the history of insertions and removals. This module
+//! sorts the entries by key first, so equal collections always serialize to
+//! the same output, which is useful for snapshots and content addressing.
+//!
+//! It is meant to be used with `#[serde(with = "autohash::serde::sorted")]`.
+//! Deserialization is unchanged. See also [`hash_sorted`], which sorts by
+//! hash first, for keys that are expensive to compare.
+//!
+//! [`hash_sorted`]: ../hash_sorted/index.html
+//!
+//! # Examples
+//!
+//! ```
+//! use autohash::serde::sorted;
+//! use autohash::AutoHashSet;
+//! use serde_test::{assert_ser_tokens, Token};
+//!
+//! struct Snapshot(AutoHashSet<u32>);
+//!
+//! impl serde::Serialize for Snapshot {
+//!     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+//!         sorted::serialize(&self.0, serializer)
+//!     }
+//! }
+//!
+//! let set = [3, 1, 2].iter().copied().collect();
+//! assert_ser_tokens(
+//!     &Snapshot(set),
+//!     &[
+//!         Token::Seq { len: Some(3) },
+//!         Token::U32(1),
+//!         Token::U32(2),
+//!         Token::U32(3),
+//!         Token::SeqEnd,
+//!     ],
+//! );
+//! ```
+
+use alloc::vec::Vec;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{AutoHash, AutoHashMap, AutoHashSet};
+
+/// A collection that can be serialized in a deterministic order.
+///
+/// This is implemented for `AutoHashMap` and `AutoHashSet`, for use with
+/// [`sorted::serialize`] and [`hash_sorted::serialize`].
+///
+/// [`sorted::serialize`]: fn.serialize.html
+/// [`hash_sorted::serialize`]: ../hash_sorted/fn.serialize.html
+pub trait SerializeSorted {
+    /// Serialize this value with its entries sorted by key.
+    fn serialize_sorted<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+
+    /// Serialize this value with its entries sorted by the keys' own
+    /// `AutoHash` values, then by key for equal hashes.
+    fn serialize_hash_sorted<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+/// Serializes a map or set with its entries sorted by key.
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+#[inline]
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: SerializeSorted + ?Sized,
+    S: Serializer,
+{
+    value.serialize_sorted(serializer)
+}
+
+/// Deserializes a collection normally.
+///
+/// This exists so the module can be used with `#[serde(with = ...)]`.
+///
+/// # Errors
+///
+/// Returns any error from the deserializer.
+#[inline]
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer)
+}
+
+impl<K, V, M> SerializeSorted for AutoHashMap<K, V, M>
+where
+    K: Serialize + Ord + AutoHash,
+    V: Serialize,
+{
+    fn serialize_sorted<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        serializer.collect_map(entries)
+    }
+
+    fn serialize_hash_sorted<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut entries: Vec<_> = self.iter().map(|(k, v)| (k.get_hash(), k, v)).collect();
+        entries.sort_unstable_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        serializer.collect_map(entries.into_iter().map(|(_, k, v)| (k, v)))
+    }
+}
+
+impl<T, M> SerializeSorted for AutoHashSet<T, M>
+where
+    T: Serialize + Ord + AutoHash,
+{
+    fn serialize_sorted<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut values: Vec<_> = self.iter().collect();
+        values.sort_unstable();
+        serializer.collect_seq(values)
+    }
+
+    fn serialize_hash_sorted<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut values: Vec<_> = self.iter().map(|v| (v.get_hash(), v)).collect();
+        values.sort_unstable();
+        serializer.collect_seq(values.into_iter().map(|(_, v)| v))
+    }
+}
@@ -0,0 +1,195 @@
+//! Deserialize maps and sets, rejecting duplicate keys.
+//!
+//! The default `Deserialize` implementations for `AutoHashMap` and
+//! `AutoHashSet` insert every entry in order, so a later duplicate key
+//! silently replaces an earlier one. This module instead returns an error
+//! naming the first duplicate key, using its `Debug` representation.
+//!
+//! It is meant to be used with `#[serde(with = "autohash::serde::strict")]`.
+//! Serialization is unchanged.
+//!
+//! # Examples
+//!
+//! ```
+//! use autohash::serde::strict;
+//! use autohash::AutoHashSet;
+//! use serde::de::value::{Error, SeqDeserializer};
+//!
+//! let unique = SeqDeserializer::<_, Error>::new(vec![1, 2, 3].into_iter());
+//! let set: AutoHashSet<i32> = strict::deserialize(unique).unwrap();
+//! assert_eq!(set.len(), 3);
+//!
+//! let duplicate = SeqDeserializer::<_, Error>::new(vec![1, 2, 1].into_iter());
+//! let error = strict::deserialize::<_, AutoHashSet<i32>>(duplicate).unwrap_err();
+//! assert_eq!(error.to_string(), "duplicate set element 1");
+//! ```
+
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::external_trait_impls::serde::size_hint::cautious;
+use crate::map::RawEntryMut;
+use crate::mix::HashMixer;
+use crate::{AutoHash, AutoHashMap, AutoHashSet};
+
+/// A collection that can be deserialized while rejecting duplicate keys.
+///
+/// This is implemented for `AutoHashMap` and `AutoHashSet`, for use with
+/// [`deserialize`].
+///
+/// [`deserialize`]: fn.deserialize.html
+pub trait DeserializeStrict<'de>: Sized {
+    /// Deserialize this value from the given Serde deserializer, returning an
+    /// error if any key is repeated.
+    fn deserialize_strict<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// Serializes a collection normally.
+///
+/// This exists so the module can be used with `#[serde(with = ...)]`.
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+#[inline]
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + ?Sized,
+    S: Serializer,
+{
+    value.serialize(serializer)
+}
+
+/// Deserializes a map or set, returning an error if any key is repeated.
+///
+/// # Errors
+///
+/// Returns an error if deserialization fails, or if the input contains the
+/// same key more than once.
+#[inline]
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeStrict<'de>,
+{
+    T::deserialize_strict(deserializer)
+}
+
+impl<'de, K, V, M> DeserializeStrict<'de> for AutoHashMap<K, V, M>
+where
+    K: Deserialize<'de> + Eq + AutoHash + Debug,
+    V: Deserialize<'de>,
+    M: HashMixer + Default,
+{
+    fn deserialize_strict<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MapVisitor<K, V, M> {
+            marker: PhantomData<AutoHashMap<K, V, M>>,
+        }
+
+        impl<'de, K, V, M> Visitor<'de> for MapVisitor<K, V, M>
+        where
+            K: Deserialize<'de> + Eq + AutoHash + Debug,
+            V: Deserialize<'de>,
+            M: HashMixer + Default,
+        {
+            type Value = AutoHashMap<K, V, M>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map with unique keys")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut values =
+                    AutoHashMap::with_capacity_and_mixer(cautious(map.size_hint()), M::default());
+
+                while let Some((key, value)) = map.next_entry::<K, V>()? {
+                    insert_unique(&mut values, key, value).map_err(|key| {
+                        A::Error::custom(format_args!("duplicate map key {:?}", key))
+                    })?;
+                }
+
+                Ok(values)
+            }
+        }
+
+        let visitor = MapVisitor {
+            marker: PhantomData,
+        };
+        deserializer.deserialize_map(visitor)
+    }
+}
+
+impl<'de, T, M> DeserializeStrict<'de> for AutoHashSet<T, M>
+where
+    T: Deserialize<'de> + Eq + AutoHash + Debug,
+    M: HashMixer + Default,
+{
+    fn deserialize_strict<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SeqVisitor<T, M> {
+            marker: PhantomData<AutoHashSet<T, M>>,
+        }
+
+        impl<'de, T, M> Visitor<'de> for SeqVisitor<T, M>
+        where
+            T: Deserialize<'de> + Eq + AutoHash + Debug,
+            M: HashMixer + Default,
+        {
+            type Value = AutoHashSet<T, M>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a sequence of unique elements")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values =
+                    AutoHashSet::with_capacity_and_mixer(cautious(seq.size_hint()), M::default());
+
+                while let Some(value) = seq.next_element::<T>()? {
+                    insert_unique(&mut values.map, value, ()).map_err(|value| {
+                        A::Error::custom(format_args!("duplicate set element {:?}", value))
+                    })?;
+                }
+
+                Ok(values)
+            }
+        }
+
+        let visitor = SeqVisitor {
+            marker: PhantomData,
+        };
+        deserializer.deserialize_seq(visitor)
+    }
+}
+
+/// Inserts a new key, or returns it back if it was already present.
+#[inline]
+fn insert_unique<K, V, M>(map: &mut AutoHashMap<K, V, M>, key: K, value: V) -> Result<(), K>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    let hash = key.get_hash();
+    match map.raw_entry_mut().from_key_hashed_nocheck(hash, &key) {
+        RawEntryMut::Occupied(_) => Err(key),
+        RawEntryMut::Vacant(entry) => {
+            entry.insert_hashed_nocheck(hash, key, value);
+            Ok(())
+        }
+    }
+}
@@ -1,9 +1,9 @@
 //! A hash set implemented as a `AutoHashMap` where the value is `()`.
 
-use crate::AutoHash;
+use crate::mix::{HashMixer, KeyedMix, NoMix};
 use crate::TryReserveError;
+use crate::{AutoHash, Equivalent};
 use alloc::borrow::ToOwned;
-use core::borrow::Borrow;
 use core::fmt;
 use core::iter::{Chain, FromIterator, FusedIterator};
 use core::mem;
@@ -113,11 +113,11 @@ use super::map::{self, AutoHashMap, ConsumeAllOnDrop, DrainFilterInner, Keys};
 /// [`HashMap`]: struct.HashMap.html
 /// [`PartialEq`]: https://doc.rust-lang.org/std/cmp/trait.PartialEq.html
 /// [`RefCell`]: https://doc.rust-lang.org/std/cell/struct.RefCell.html
-pub struct AutoHashSet<T> {
-    pub(crate) map: AutoHashMap<T, ()>,
+pub struct AutoHashSet<T, M = NoMix> {
+    pub(crate) map: AutoHashMap<T, (), M>,
 }
 
-impl<T: Clone> Clone for AutoHashSet<T> {
+impl<T: Clone, M: Clone> Clone for AutoHashSet<T, M> {
     fn clone(&self) -> Self {
         AutoHashSet {
             map: self.map.clone(),
@@ -129,7 +129,7 @@ impl<T: Clone> Clone for AutoHashSet<T> {
     }
 }
 
-impl<T> AutoHashSet<T> {
+impl<T> AutoHashSet<T, NoMix> {
     /// Creates an empty `HashSet`.
     ///
     /// The hash set is initially created with a capacity of 0, so it will not allocate until it
@@ -166,6 +166,103 @@ impl<T> AutoHashSet<T> {
             map: AutoHashMap::with_capacity(capacity),
         }
     }
+}
+
+impl<T> AutoHashSet<T, KeyedMix> {
+    /// Creates an empty `AutoHashSet` with a [`KeyedMix`] derived from `seed`.
+    ///
+    /// If the seed is kept secret, elements with attacker-chosen hashes, like
+    /// `RawHashed` values from untrusted input, cannot be aimed at the same
+    /// buckets to degrade the performance of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::wrappers::RawHashed;
+    /// use autohash::AutoHashSet;
+    ///
+    /// let mut set = AutoHashSet::with_seed(0x5eed);
+    /// set.insert(RawHashed::new(0, "zero"));
+    /// assert!(set.contains(&RawHashed::new(0, "zero")));
+    /// ```
+    ///
+    /// [`KeyedMix`]: ../mix/struct.KeyedMix.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_mixer(KeyedMix::new(seed))
+    }
+
+    /// Creates an empty `AutoHashSet` with a randomly keyed [`KeyedMix`].
+    ///
+    /// This is only available with the `getrandom` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a random seed could not be read from the operating system.
+    ///
+    /// [`KeyedMix`]: ../mix/struct.KeyedMix.html
+    #[cfg(feature = "getrandom")]
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new_randomized() -> Self {
+        Self::with_mixer(KeyedMix::random())
+    }
+}
+
+impl<T, M> AutoHashSet<T, M> {
+    /// Creates an empty `AutoHashSet` which will use the given mixer to
+    /// post-process the hashes of its elements.
+    ///
+    /// The hash set is initially created with a capacity of 0, so it will not allocate until it
+    /// is first inserted into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::mix::FibonacciMix;
+    /// use autohash::wrappers::U64Hash;
+    /// use autohash::AutoHashSet;
+    ///
+    /// let mut set = AutoHashSet::with_mixer(FibonacciMix);
+    /// set.insert(U64Hash(2));
+    /// assert!(set.contains(&U64Hash(2)));
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self {
+            map: AutoHashMap::with_mixer(mixer),
+        }
+    }
+
+    /// Creates an empty `AutoHashSet` with the specified capacity, which will
+    /// use the given mixer to post-process the hashes of its elements.
+    ///
+    /// The hash set will be able to hold at least `capacity` elements without
+    /// reallocating. If `capacity` is 0, the hash set will not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use autohash::mix::Murmur3Mix;
+    /// use autohash::wrappers::U64Hash;
+    /// use autohash::AutoHashSet;
+    ///
+    /// let set: AutoHashSet<U64Hash, _> = AutoHashSet::with_capacity_and_mixer(10, Murmur3Mix);
+    /// assert!(set.capacity() >= 10);
+    /// ```
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_and_mixer(capacity: usize, mixer: M) -> Self {
+        Self {
+            map: AutoHashMap::with_capacity_and_mixer(capacity, mixer),
+        }
+    }
+
+    /// Returns a reference to the set's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        self.map.mixer()
+    }
 
     /// Returns the number of elements the set can hold without reallocating.
     ///
@@ -340,9 +437,10 @@ impl<T> AutoHashSet<T> {
     }
 }
 
-impl<T> AutoHashSet<T>
+impl<T, M> AutoHashSet<T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer,
 {
     /// Reserves capacity for at least `additional` more elements to be inserted
     /// in the `HashSet`. The collection may reserve more space to avoid
@@ -457,7 +555,7 @@ where
     /// assert_eq!(diff, [4].iter().collect());
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T> {
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T, M> {
         Difference {
             iter: self.iter(),
             other,
@@ -486,7 +584,7 @@ where
     /// assert_eq!(diff1, [1, 4].iter().collect());
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T> {
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T, M> {
         SymmetricDifference {
             iter: self.difference(other).chain(other.difference(self)),
         }
@@ -511,7 +609,7 @@ where
     /// assert_eq!(intersection, [2, 3].iter().collect());
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T> {
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T, M> {
         let (smaller, larger) = if self.len() <= other.len() {
             (self, other)
         } else {
@@ -542,7 +640,7 @@ where
     /// assert_eq!(union, [1, 2, 3, 4].iter().collect());
     /// ```
     #[cfg_attr(feature = "inline-more", inline)]
-    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T> {
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, M> {
         let (smaller, larger) = if self.len() >= other.len() {
             (self, other)
         } else {
@@ -555,9 +653,9 @@ where
 
     /// Returns `true` if the set contains a value.
     ///
-    /// The value may be any borrowed form of the set's value type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the value type.
+    /// The value may be any type that is [`Equivalent`] to the set's value
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the value type.
     ///
     /// # Examples
     ///
@@ -569,22 +667,21 @@ where
     /// assert_eq!(set.contains(&4), false);
     /// ```
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn contains<Q: ?Sized>(&self, value: &Q) -> bool
     where
-        T: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<T>,
     {
         self.map.contains_key(value)
     }
 
     /// Returns a reference to the value in the set, if any, that is equal to the given value.
     ///
-    /// The value may be any borrowed form of the set's value type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the value type.
+    /// The value may be any type that is [`Equivalent`] to the set's value
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the value type.
     ///
     /// # Examples
     ///
@@ -596,13 +693,12 @@ where
     /// assert_eq!(set.get(&4), None);
     /// ```
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn get<Q: ?Sized>(&self, value: &Q) -> Option<&T>
     where
-        T: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<T>,
     {
         // Avoid `Option::map` because it bloats LLVM IR.
         match self.map.get_key_value(value) {
@@ -657,8 +753,7 @@ where
     #[inline]
     pub fn get_or_insert_owned<Q: ?Sized>(&mut self, value: &Q) -> &T
     where
-        T: Borrow<Q>,
-        Q: AutoHash + Eq + ToOwned<Owned = T>,
+        Q: AutoHash + Equivalent<T> + ToOwned<Owned = T>,
     {
         // Although the raw entry gives us `&mut T`, we only return `&T` to be consistent with
         // `get`. Key mutation is "raw" because you're not supposed to affect `Eq` or `Hash`.
@@ -690,8 +785,7 @@ where
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn get_or_insert_with<Q: ?Sized, F>(&mut self, value: &Q, f: F) -> &T
     where
-        T: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<T>,
         F: FnOnce(&Q) -> T,
     {
         // Although the raw entry gives us `&mut T`, we only return `&T` to be consistent with
@@ -821,9 +915,9 @@ where
     /// Removes a value from the set. Returns whether the value was
     /// present in the set.
     ///
-    /// The value may be any borrowed form of the set's value type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the value type.
+    /// The value may be any type that is [`Equivalent`] to the set's value
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the value type.
     ///
     /// # Examples
     ///
@@ -837,22 +931,21 @@ where
     /// assert_eq!(set.remove(&2), false);
     /// ```
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn remove<Q: ?Sized>(&mut self, value: &Q) -> bool
     where
-        T: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<T>,
     {
         self.map.remove(value).is_some()
     }
 
     /// Removes and returns the value in the set, if any, that is equal to the given one.
     ///
-    /// The value may be any borrowed form of the set's value type, but
-    /// [`Hash`] and [`Eq`] on the borrowed form *must* match those for
-    /// the value type.
+    /// The value may be any type that is [`Equivalent`] to the set's value
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the value type.
     ///
     /// # Examples
     ///
@@ -864,13 +957,12 @@ where
     /// assert_eq!(set.take(&2), None);
     /// ```
     ///
-    /// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
-    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`AutoHash`]: ../trait.AutoHash.html
+    /// [`Equivalent`]: ../trait.Equivalent.html
     #[cfg_attr(feature = "inline-more", inline)]
     pub fn take<Q: ?Sized>(&mut self, value: &Q) -> Option<T>
     where
-        T: Borrow<Q>,
-        Q: AutoHash + Eq,
+        Q: AutoHash + Equivalent<T>,
     {
         // Avoid `Option::map` because it bloats LLVM IR.
         match self.map.remove_entry(value) {
@@ -880,9 +972,10 @@ where
     }
 }
 
-impl<T> PartialEq for AutoHashSet<T>
+impl<T, M> PartialEq for AutoHashSet<T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer,
 {
     fn eq(&self, other: &Self) -> bool {
         if self.len() != other.len() {
@@ -893,20 +986,27 @@ where
     }
 }
 
-impl<T> Eq for AutoHashSet<T> where T: Eq + AutoHash {}
+impl<T, M> Eq for AutoHashSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+}
 
-impl<T> fmt::Debug for AutoHashSet<T>
+impl<T, M> fmt::Debug for AutoHashSet<T, M>
 where
     T: Eq + AutoHash + fmt::Debug,
+    M: HashMixer,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_set().entries(self.iter()).finish()
     }
 }
 
-impl<T> FromIterator<T> for AutoHashSet<T>
+impl<T, M> FromIterator<T> for AutoHashSet<T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer + Default,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
@@ -916,9 +1016,10 @@ where
     }
 }
 
-impl<T> Extend<T> for AutoHashSet<T>
+impl<T, M> Extend<T> for AutoHashSet<T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
@@ -938,9 +1039,10 @@ where
     }
 }
 
-impl<'a, T> Extend<&'a T> for AutoHashSet<T>
+impl<'a, T, M> Extend<&'a T> for AutoHashSet<T, M>
 where
     T: 'a + Eq + AutoHash + Copy,
+    M: HashMixer,
 {
     #[cfg_attr(feature = "inline-more", inline)]
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
@@ -960,8 +1062,8 @@ where
     }
 }
 
-impl<T> Default for AutoHashSet<T> {
-    /// Creates an empty `HashSet<T>` with the `Default` value for the hasher.
+impl<T, M: Default> Default for AutoHashSet<T, M> {
+    /// Creates an empty `HashSet<T, M>` with the `Default` value for the mixer.
     #[cfg_attr(feature = "inline-more", inline)]
     fn default() -> Self {
         Self {
@@ -970,11 +1072,12 @@ impl<T> Default for AutoHashSet<T> {
     }
 }
 
-impl<T> BitOr<&AutoHashSet<T>> for &AutoHashSet<T>
+impl<T, M> BitOr<&AutoHashSet<T, M>> for &AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Clone,
+    M: HashMixer + Default,
 {
-    type Output = AutoHashSet<T>;
+    type Output = AutoHashSet<T, M>;
 
     /// Returns the union of `self` and `rhs` as a new `HashSet<T>`.
     ///
@@ -996,16 +1099,17 @@ where
     /// }
     /// assert_eq!(i, expected.len());
     /// ```
-    fn bitor(self, rhs: &AutoHashSet<T>) -> AutoHashSet<T> {
+    fn bitor(self, rhs: &AutoHashSet<T, M>) -> AutoHashSet<T, M> {
         self.union(rhs).cloned().collect()
     }
 }
 
-impl<T> BitAnd<&AutoHashSet<T>> for &AutoHashSet<T>
+impl<T, M> BitAnd<&AutoHashSet<T, M>> for &AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Clone,
+    M: HashMixer + Default,
 {
-    type Output = AutoHashSet<T>;
+    type Output = AutoHashSet<T, M>;
 
     /// Returns the intersection of `self` and `rhs` as a new `HashSet<T>`.
     ///
@@ -1027,16 +1131,17 @@ where
     /// }
     /// assert_eq!(i, expected.len());
     /// ```
-    fn bitand(self, rhs: &AutoHashSet<T>) -> AutoHashSet<T> {
+    fn bitand(self, rhs: &AutoHashSet<T, M>) -> AutoHashSet<T, M> {
         self.intersection(rhs).cloned().collect()
     }
 }
 
-impl<T> BitXor<&AutoHashSet<T>> for &AutoHashSet<T>
+impl<T, M> BitXor<&AutoHashSet<T, M>> for &AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Clone,
+    M: HashMixer + Default,
 {
-    type Output = AutoHashSet<T>;
+    type Output = AutoHashSet<T, M>;
 
     /// Returns the symmetric difference of `self` and `rhs` as a new `HashSet<T>`.
     ///
@@ -1058,16 +1163,17 @@ where
     /// }
     /// assert_eq!(i, expected.len());
     /// ```
-    fn bitxor(self, rhs: &AutoHashSet<T>) -> AutoHashSet<T> {
+    fn bitxor(self, rhs: &AutoHashSet<T, M>) -> AutoHashSet<T, M> {
         self.symmetric_difference(rhs).cloned().collect()
     }
 }
 
-impl<T> Sub<&AutoHashSet<T>> for &AutoHashSet<T>
+impl<T, M> Sub<&AutoHashSet<T, M>> for &AutoHashSet<T, M>
 where
     T: Eq + AutoHash + Clone,
+    M: HashMixer + Default,
 {
-    type Output = AutoHashSet<T>;
+    type Output = AutoHashSet<T, M>;
 
     /// Returns the difference of `self` and `rhs` as a new `HashSet<T>`.
     ///
@@ -1089,7 +1195,7 @@ where
     /// }
     /// assert_eq!(i, expected.len());
     /// ```
-    fn sub(self, rhs: &AutoHashSet<T>) -> AutoHashSet<T> {
+    fn sub(self, rhs: &AutoHashSet<T, M>) -> AutoHashSet<T, M> {
         self.difference(rhs).cloned().collect()
     }
 }
@@ -1149,11 +1255,11 @@ where
 ///
 /// [`HashSet`]: struct.HashSet.html
 /// [`intersection`]: struct.HashSet.html#method.intersection
-pub struct Intersection<'a, T> {
+pub struct Intersection<'a, T, M = NoMix> {
     // iterator of the first set
     iter: Iter<'a, T>,
     // the second set
-    other: &'a AutoHashSet<T>,
+    other: &'a AutoHashSet<T, M>,
 }
 
 /// A lazy iterator producing elements in the difference of `HashSet`s.
@@ -1163,11 +1269,11 @@ pub struct Intersection<'a, T> {
 ///
 /// [`HashSet`]: struct.HashSet.html
 /// [`difference`]: struct.HashSet.html#method.difference
-pub struct Difference<'a, T> {
+pub struct Difference<'a, T, M = NoMix> {
     // iterator of the first set
     iter: Iter<'a, T>,
     // the second set
-    other: &'a AutoHashSet<T>,
+    other: &'a AutoHashSet<T, M>,
 }
 
 /// A lazy iterator producing elements in the symmetric difference of `HashSet`s.
@@ -1177,8 +1283,8 @@ pub struct Difference<'a, T> {
 ///
 /// [`HashSet`]: struct.HashSet.html
 /// [`symmetric_difference`]: struct.HashSet.html#method.symmetric_difference
-pub struct SymmetricDifference<'a, T> {
-    iter: Chain<Difference<'a, T>, Difference<'a, T>>,
+pub struct SymmetricDifference<'a, T, M = NoMix> {
+    iter: Chain<Difference<'a, T, M>, Difference<'a, T, M>>,
 }
 
 /// A lazy iterator producing elements in the union of `HashSet`s.
@@ -1188,11 +1294,11 @@ pub struct SymmetricDifference<'a, T> {
 ///
 /// [`HashSet`]: struct.HashSet.html
 /// [`union`]: struct.HashSet.html#method.union
-pub struct Union<'a, T> {
-    iter: Chain<Iter<'a, T>, Difference<'a, T>>,
+pub struct Union<'a, T, M = NoMix> {
+    iter: Chain<Iter<'a, T>, Difference<'a, T, M>>,
 }
 
-impl<'a, T> IntoIterator for &'a AutoHashSet<T> {
+impl<'a, T, M> IntoIterator for &'a AutoHashSet<T, M> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -1202,7 +1308,7 @@ impl<'a, T> IntoIterator for &'a AutoHashSet<T> {
     }
 }
 
-impl<T> IntoIterator for AutoHashSet<T> {
+impl<T, M> IntoIterator for AutoHashSet<T, M> {
     type Item = T;
     type IntoIter = IntoIter<T>;
 
@@ -1365,7 +1471,7 @@ where
 
 impl<K, F> FusedIterator for DrainFilter<'_, K, F> where F: FnMut(&K) -> bool {}
 
-impl<T> Clone for Intersection<'_, T> {
+impl<T, M> Clone for Intersection<'_, T, M> {
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         Intersection {
@@ -1375,9 +1481,10 @@ impl<T> Clone for Intersection<'_, T> {
     }
 }
 
-impl<'a, T> Iterator for Intersection<'a, T>
+impl<'a, T, M> Iterator for Intersection<'a, T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer,
 {
     type Item = &'a T;
 
@@ -1398,18 +1505,24 @@ where
     }
 }
 
-impl<T> fmt::Debug for Intersection<'_, T>
+impl<T, M> fmt::Debug for Intersection<'_, T, M>
 where
     T: fmt::Debug + Eq + AutoHash,
+    M: HashMixer,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
 }
 
-impl<T> FusedIterator for Intersection<'_, T> where T: Eq + AutoHash {}
+impl<T, M> FusedIterator for Intersection<'_, T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+}
 
-impl<T> Clone for Difference<'_, T> {
+impl<T, M> Clone for Difference<'_, T, M> {
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         Difference {
@@ -1419,9 +1532,10 @@ impl<T> Clone for Difference<'_, T> {
     }
 }
 
-impl<'a, T> Iterator for Difference<'a, T>
+impl<'a, T, M> Iterator for Difference<'a, T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer,
 {
     type Item = &'a T;
 
@@ -1442,18 +1556,24 @@ where
     }
 }
 
-impl<T> FusedIterator for Difference<'_, T> where T: Eq + AutoHash {}
+impl<T, M> FusedIterator for Difference<'_, T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+}
 
-impl<T> fmt::Debug for Difference<'_, T>
+impl<T, M> fmt::Debug for Difference<'_, T, M>
 where
     T: fmt::Debug + Eq + AutoHash,
+    M: HashMixer,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
 }
 
-impl<T> Clone for SymmetricDifference<'_, T> {
+impl<T, M> Clone for SymmetricDifference<'_, T, M> {
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         SymmetricDifference {
@@ -1462,9 +1582,10 @@ impl<T> Clone for SymmetricDifference<'_, T> {
     }
 }
 
-impl<'a, T> Iterator for SymmetricDifference<'a, T>
+impl<'a, T, M> Iterator for SymmetricDifference<'a, T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer,
 {
     type Item = &'a T;
 
@@ -1478,18 +1599,24 @@ where
     }
 }
 
-impl<T> FusedIterator for SymmetricDifference<'_, T> where T: Eq + AutoHash {}
+impl<T, M> FusedIterator for SymmetricDifference<'_, T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+}
 
-impl<T> fmt::Debug for SymmetricDifference<'_, T>
+impl<T, M> fmt::Debug for SymmetricDifference<'_, T, M>
 where
     T: fmt::Debug + Eq + AutoHash,
+    M: HashMixer,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
 }
 
-impl<T> Clone for Union<'_, T> {
+impl<T, M> Clone for Union<'_, T, M> {
     #[cfg_attr(feature = "inline-more", inline)]
     fn clone(&self) -> Self {
         Union {
@@ -1498,20 +1625,27 @@ impl<T> Clone for Union<'_, T> {
     }
 }
 
-impl<T> FusedIterator for Union<'_, T> where T: Eq + AutoHash {}
+impl<T, M> FusedIterator for Union<'_, T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+}
 
-impl<T> fmt::Debug for Union<'_, T>
+impl<T, M> fmt::Debug for Union<'_, T, M>
 where
     T: fmt::Debug + Eq + AutoHash,
+    M: HashMixer,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
     }
 }
 
-impl<'a, T> Iterator for Union<'a, T>
+impl<'a, T, M> Iterator for Union<'a, T, M>
 where
     T: Eq + AutoHash,
+    M: HashMixer,
 {
     type Item = &'a T;
 
@@ -0,0 +1,936 @@
+//! A hash map that stores the hash of each key beside its entry.
+
+use crate::mix::{HashMixer, NoMix};
+use crate::TryReserveError;
+use crate::{AutoHash, Equivalent};
+use core::fmt::{self, Debug};
+use core::iter::{FromIterator, FusedIterator};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Index;
+use hashbrown::raw::{RawDrain, RawIntoIter, RawIter, RawTable};
+
+/// A hash map that saves the mixed hash of every key in its table.
+///
+/// An [`AutoHashMap`] asks each key for its [`AutoHash`] value again whenever
+/// the table is resized. That is cheap for keys that already hold their hash,
+/// but expensive for keys like `wrappers::AutoHashed` that hash their entire
+/// value each time. `StoredHashMap` calls `get_hash` exactly once for each
+/// key that is inserted, and once for each query, at the cost of 8 more bytes
+/// for every entry.
+///
+/// The stored hash also rejects entries whose full hash differs from the
+/// query before the keys are compared with [`Equivalent`].
+///
+/// [`AutoHashMap`]: ../../map/struct.AutoHashMap.html
+/// [`AutoHash`]: ../../trait.AutoHash.html
+/// [`Equivalent`]: ../../trait.Equivalent.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::stored::StoredHashMap;
+/// use autohash::wrappers::AutoHashed;
+/// use std::collections::hash_map::DefaultHasher;
+///
+/// type Key = AutoHashed<String, DefaultHasher>;
+///
+/// let mut map = StoredHashMap::new();
+/// for i in 0..100 {
+///     map.insert(Key::from(i.to_string()), i);
+/// }
+///
+/// // Growing the table did not hash any of the strings again.
+/// map.reserve(1000);
+/// assert_eq!(map[&Key::from("42".to_string())], 42);
+/// ```
+pub struct StoredHashMap<K, V, M = NoMix> {
+    mixer: M,
+    table: RawTable<(u64, K, V)>,
+}
+
+impl<K: Clone, V: Clone, M: Clone> Clone for StoredHashMap<K, V, M> {
+    fn clone(&self) -> Self {
+        StoredHashMap {
+            mixer: self.mixer.clone(),
+            table: self.table.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.table.clone_from(&source.table);
+
+        // Update mixer after table, in case the table clone panics.
+        self.mixer.clone_from(&source.mixer);
+    }
+}
+
+/// Rehashes an entry by reading its stored hash, without calling `get_hash`.
+#[cfg_attr(feature = "inline-more", inline)]
+fn stored_hash<K, V>(x: &(u64, K, V)) -> u64 {
+    x.0
+}
+
+/// Compares the stored hash before calling `Equivalent` on the keys.
+#[cfg_attr(feature = "inline-more", inline)]
+fn equivalent_key<Q, K, V>(hash: u64, k: &Q) -> impl Fn(&(u64, K, V)) -> bool + '_
+where
+    Q: ?Sized + Equivalent<K>,
+{
+    move |x| hash == x.0 && k.equivalent(&x.1)
+}
+
+impl<K, V> StoredHashMap<K, V, NoMix> {
+    /// Creates an empty `StoredHashMap`.
+    ///
+    /// The hash map is initially created with a capacity of 0, so it will not
+    /// allocate until it is first inserted into.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self::with_mixer(NoMix)
+    }
+
+    /// Creates an empty `StoredHashMap` with the specified capacity.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_mixer(capacity, NoMix)
+    }
+}
+
+impl<K, V, M> StoredHashMap<K, V, M> {
+    /// Creates an empty `StoredHashMap` which will use the given mixer to
+    /// post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self {
+            mixer,
+            table: RawTable::new(),
+        }
+    }
+
+    /// Creates an empty `StoredHashMap` with the specified capacity, which
+    /// will use the given mixer to post-process the hashes of its keys.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_and_mixer(capacity: usize, mixer: M) -> Self {
+        Self {
+            mixer,
+            table: RawTable::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a reference to the map's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        &self.mixer
+    }
+
+    /// Returns the number of elements the map can hold without reallocating.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+
+    /// An iterator visiting all keys in arbitrary order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// An iterator visiting all values mutably in arbitrary order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        // Here we tie the lifetime of self to the iter.
+        unsafe {
+            Iter {
+                inner: self.table.iter(),
+                marker: PhantomData,
+            }
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, with
+    /// mutable references to the values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        // Here we tie the lifetime of self to the iter.
+        unsafe {
+            IterMut {
+                inner: self.table.iter(),
+                marker: PhantomData,
+            }
+        }
+    }
+
+    /// Returns the number of elements in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clears the map, returning all key-value pairs as an iterator. Keeps the
+    /// allocated memory for reuse.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        Drain {
+            inner: self.table.drain(),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k,&mut v)` returns `false`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        // Here we only use `iter` as a temporary, preventing use-after-free
+        unsafe {
+            for item in self.table.iter() {
+                let &mut (_, ref key, ref mut value) = item.as_mut();
+                if !f(key, value) {
+                    self.table.erase(item);
+                }
+            }
+        }
+    }
+
+    /// Clears the map, removing all key-value pairs. Keeps the allocated memory
+    /// for reuse.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the `StoredHashMap`. The collection may reserve more space to avoid
+    /// frequent reallocations.
+    ///
+    /// Unlike `AutoHashMap`, this does not need to hash any of the keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows [`usize`].
+    ///
+    /// [`usize`]: https://doc.rust-lang.org/std/primitive.usize.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn reserve(&mut self, additional: usize) {
+        self.table.reserve(additional, stored_hash);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the `StoredHashMap`. The collection may reserve more space
+    /// to avoid frequent reallocations.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.table.try_reserve(additional, stored_hash)
+    }
+
+    /// Shrinks the capacity of the map as much as possible. It will drop
+    /// down as much as possible while maintaining the internal rules
+    /// and possibly leaving some space in accordance with the resize policy.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shrink_to_fit(&mut self) {
+        self.table.shrink_to(0, stored_hash);
+    }
+
+    /// Shrinks the capacity of the map with a lower limit. It will drop
+    /// down no lower than the supplied limit while maintaining the internal rules
+    /// and possibly leaving some space in accordance with the resize policy.
+    ///
+    /// This function does nothing if the current capacity is smaller than the
+    /// supplied minimum capacity.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.table.shrink_to(min_capacity, stored_hash);
+    }
+}
+
+impl<K, V, M> StoredHashMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn hash<Q: AutoHash + ?Sized>(&self, k: &Q) -> u64 {
+        self.mixer.mix(k.get_hash())
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any type that is [`Equivalent`] to the map's key
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the key type.
+    ///
+    /// [`AutoHash`]: ../../trait.AutoHash.html
+    /// [`Equivalent`]: ../../trait.Equivalent.html
+    #[inline]
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_inner(k) {
+            Some((_, _, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    #[inline]
+    pub fn get_key_value<Q: ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.get_inner(k) {
+            Some((_, key, value)) => Some((key, value)),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn get_inner<Q: ?Sized>(&self, k: &Q) -> Option<&(u64, K, V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.hash(k);
+        self.table.get(hash, equivalent_key(hash, k))
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        self.get_inner(k).is_some()
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.hash(k);
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.table.get_mut(hash, equivalent_key(hash, k)) {
+            Some(&mut (_, _, ref mut v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned. The key is not updated, though.
+    ///
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        let hash = self.hash(&k);
+        if let Some((_, _, item)) = self.table.get_mut(hash, equivalent_key(hash, &k)) {
+            Some(mem::replace(item, v))
+        } else {
+            self.table.insert(hash, (hash, k, v), stored_hash);
+            None
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove<Q: ?Sized>(&mut self, k: &Q) -> Option<V>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.remove_entry(k) {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove_entry<Q: ?Sized>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        Q: AutoHash + Equivalent<K>,
+    {
+        let hash = self.hash(k);
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.table.remove_entry(hash, equivalent_key(hash, k)) {
+            Some((_, k, v)) => Some((k, v)),
+            None => None,
+        }
+    }
+}
+
+impl<K, V, M> PartialEq for StoredHashMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    V: PartialEq,
+    M: HashMixer,
+{
+    /// Compares the maps by looking up each key with its stored hash, so
+    /// `get_hash` is only called again if a key is missing from `other` under
+    /// that hash, like when the maps use differently seeded mixers.
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        // Here we only use `iter` as a temporary, tied to the borrow of `self`.
+        unsafe {
+            self.table.iter().all(|bucket| {
+                let (hash, key, value) = bucket.as_ref();
+                let found = match other.table.get(*hash, equivalent_key(*hash, key)) {
+                    Some((_, _, v)) => Some(v),
+                    None => other.get(key),
+                };
+                found == Some(value)
+            })
+        }
+    }
+}
+
+impl<K, V, M> Eq for StoredHashMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    V: Eq,
+    M: HashMixer,
+{
+}
+
+impl<K, V, M> Debug for StoredHashMap<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, M: Default> Default for StoredHashMap<K, V, M> {
+    /// Creates an empty `StoredHashMap<K, V, M>`, with the `Default` value for the mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self::with_mixer(M::default())
+    }
+}
+
+impl<K, Q: ?Sized, V, M> Index<&Q> for StoredHashMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    Q: AutoHash + Equivalent<K>,
+    M: HashMixer,
+{
+    type Output = V;
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the `StoredHashMap`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V, M> FromIterator<(K, V)> for StoredHashMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let iter = iter.into_iter();
+        let mut map = Self::with_capacity_and_mixer(iter.size_hint().0, M::default());
+        iter.for_each(|(k, v)| {
+            map.insert(k, v);
+        });
+        map
+    }
+}
+
+/// Inserts all new key-values from the iterator and replaces values with existing
+/// keys with new values returned from the iterator.
+impl<K, V, M> Extend<(K, V)> for StoredHashMap<K, V, M>
+where
+    K: Eq + AutoHash,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        // Keys may be already present or show multiple times in the iterator.
+        // Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map
+        // will only resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            (iter.size_hint().0 + 1) / 2
+        };
+        self.reserve(reserve);
+        iter.for_each(move |(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<'a, K, V, M> Extend<(&'a K, &'a V)> for StoredHashMap<K, V, M>
+where
+    K: Eq + AutoHash + Copy,
+    V: Copy,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a StoredHashMap<K, V, M> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a mut StoredHashMap<K, V, M> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, M> IntoIterator for StoredHashMap<K, V, M> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Creates a consuming iterator, that is, one that moves each key-value
+    /// pair out of the map in arbitrary order. The map cannot be used after
+    /// calling this.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            inner: self.table.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the entries of a `StoredHashMap`.
+///
+/// This `struct` is created by the [`iter`] method on [`StoredHashMap`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.StoredHashMap.html#method.iter
+/// [`StoredHashMap`]: struct.StoredHashMap.html
+pub struct Iter<'a, K, V> {
+    inner: RawIter<(u64, K, V)>,
+    marker: PhantomData<(&'a K, &'a V)>,
+}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K, V> Clone for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(x) => unsafe {
+                let r = x.as_ref();
+                Some((&r.1, &r.2))
+            },
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// A mutable iterator over the entries of a `StoredHashMap`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`StoredHashMap`]. See its
+/// documentation for more.
+///
+/// [`iter_mut`]: struct.StoredHashMap.html#method.iter_mut
+/// [`StoredHashMap`]: struct.StoredHashMap.html
+pub struct IterMut<'a, K, V> {
+    inner: RawIter<(u64, K, V)>,
+    // To ensure invariance with respect to V
+    marker: PhantomData<(&'a K, &'a mut V)>,
+}
+
+// We override the default Send impl which has K: Sync instead of K: Send. Both
+// are correct, but this one is more general since it allows keys which
+// implement Send but not Sync.
+unsafe impl<K: Send, V: Send> Send for IterMut<'_, K, V> {}
+
+impl<K, V> IterMut<'_, K, V> {
+    /// Returns a iterator of references over the remaining items.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub(super) fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some(x) => unsafe {
+                let r = x.as_mut();
+                Some((&r.1, &mut r.2))
+            },
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+impl<K, V> fmt::Debug for IterMut<'_, K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An owning iterator over the entries of a `StoredHashMap`.
+///
+/// This `struct` is created by the [`into_iter`] method on [`StoredHashMap`]
+/// (provided by the `IntoIterator` trait). See its documentation for more.
+///
+/// [`into_iter`]: struct.StoredHashMap.html#method.into_iter
+/// [`StoredHashMap`]: struct.StoredHashMap.html
+pub struct IntoIter<K, V> {
+    inner: RawIntoIter<(u64, K, V)>,
+}
+
+impl<K, V> IntoIter<K, V> {
+    /// Returns a iterator of references over the remaining items.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub(super) fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.iter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(K, V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some((_, k, v)) => Some((k, v)),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+impl<K: Debug, V: Debug> fmt::Debug for IntoIter<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the keys of a `StoredHashMap`.
+///
+/// This `struct` is created by the [`keys`] method on [`StoredHashMap`]. See its
+/// documentation for more.
+///
+/// [`keys`]: struct.StoredHashMap.html#method.keys
+/// [`StoredHashMap`]: struct.StoredHashMap.html
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K, V> Clone for Keys<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Keys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Debug, V> fmt::Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of a `StoredHashMap`.
+///
+/// This `struct` is created by the [`values`] method on [`StoredHashMap`]. See its
+/// documentation for more.
+///
+/// [`values`]: struct.StoredHashMap.html#method.values
+/// [`StoredHashMap`]: struct.StoredHashMap.html
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K, V> Clone for Values<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Values {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V: Debug> fmt::Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a V> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// A mutable iterator over the values of a `StoredHashMap`.
+///
+/// This `struct` is created by the [`values_mut`] method on [`StoredHashMap`]. See its
+/// documentation for more.
+///
+/// [`values_mut`]: struct.StoredHashMap.html#method.values_mut
+/// [`StoredHashMap`]: struct.StoredHashMap.html
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a mut V> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some((_, v)) => Some(v),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> fmt::Debug for ValuesMut<'_, K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.inner.iter()).finish()
+    }
+}
+
+/// A draining iterator over the entries of a `StoredHashMap`.
+///
+/// This `struct` is created by the [`drain`] method on [`StoredHashMap`]. See its
+/// documentation for more.
+///
+/// [`drain`]: struct.StoredHashMap.html#method.drain
+/// [`StoredHashMap`]: struct.StoredHashMap.html
+pub struct Drain<'a, K, V> {
+    inner: RawDrain<'a, (u64, K, V)>,
+}
+
+impl<K, V> Drain<'_, K, V> {
+    /// Returns a iterator of references over the remaining items.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub(super) fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.inner.iter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Drain<'a, K, V> {
+    type Item = (K, V);
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<(K, V)> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.inner.next() {
+            Some((_, k, v)) => Some((k, v)),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+impl<K, V> ExactSizeIterator for Drain<'_, K, V> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+impl<K, V> FusedIterator for Drain<'_, K, V> {}
+
+impl<K, V> fmt::Debug for Drain<'_, K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
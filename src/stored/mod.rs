@@ -0,0 +1,16 @@
+//! Maps and sets that store the hash of each key in the table.
+//!
+//! [`StoredHashMap`] and [`StoredHashSet`] save the mixed `AutoHash` value of
+//! each key beside its entry, so resizing the table never calls `get_hash`
+//! again, and lookups compare the full stored hash before comparing keys.
+//! This is worthwhile for keys whose `get_hash` does real work, like
+//! `wrappers::AutoHashed`, at the cost of 8 more bytes for every entry.
+//!
+//! [`StoredHashMap`]: map/struct.StoredHashMap.html
+//! [`StoredHashSet`]: set/struct.StoredHashSet.html
+
+pub mod map;
+pub mod set;
+
+pub use self::map::StoredHashMap;
+pub use self::set::StoredHashSet;
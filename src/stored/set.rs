@@ -0,0 +1,476 @@
+//! A hash set that stores the hash of each value beside it.
+
+use super::map::{self, Keys, StoredHashMap};
+use crate::mix::{HashMixer, NoMix};
+use crate::TryReserveError;
+use crate::{AutoHash, Equivalent};
+use core::fmt;
+use core::iter::{FromIterator, FusedIterator};
+
+/// A hash set that saves the mixed hash of every value in its table.
+///
+/// This is implemented as a [`StoredHashMap`] where the value is `()`, so it
+/// also calls `get_hash` exactly once for each value that is inserted, and
+/// never while resizing.
+///
+/// [`StoredHashMap`]: ../map/struct.StoredHashMap.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::stored::StoredHashSet;
+///
+/// let mut set: StoredHashSet<u32> = (0..10).collect();
+/// assert!(set.contains(&5));
+/// set.shrink_to_fit();
+/// assert_eq!(set.len(), 10);
+/// ```
+pub struct StoredHashSet<T, M = NoMix> {
+    map: StoredHashMap<T, (), M>,
+}
+
+impl<T: Clone, M: Clone> Clone for StoredHashSet<T, M> {
+    fn clone(&self) -> Self {
+        StoredHashSet {
+            map: self.map.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.map.clone_from(&source.map);
+    }
+}
+
+impl<T> StoredHashSet<T, NoMix> {
+    /// Creates an empty `StoredHashSet`.
+    ///
+    /// The hash set is initially created with a capacity of 0, so it will not
+    /// allocate until it is first inserted into.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn new() -> Self {
+        Self {
+            map: StoredHashMap::new(),
+        }
+    }
+
+    /// Creates an empty `StoredHashSet` with the specified capacity.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: StoredHashMap::with_capacity(capacity),
+        }
+    }
+}
+
+impl<T, M> StoredHashSet<T, M> {
+    /// Creates an empty `StoredHashSet` which will use the given mixer to
+    /// post-process the hashes of its values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_mixer(mixer: M) -> Self {
+        Self {
+            map: StoredHashMap::with_mixer(mixer),
+        }
+    }
+
+    /// Creates an empty `StoredHashSet` with the specified capacity, which
+    /// will use the given mixer to post-process the hashes of its values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn with_capacity_and_mixer(capacity: usize, mixer: M) -> Self {
+        Self {
+            map: StoredHashMap::with_capacity_and_mixer(capacity, mixer),
+        }
+    }
+
+    /// Returns a reference to the set's [`HashMixer`].
+    ///
+    /// [`HashMixer`]: ../../mix/trait.HashMixer.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn mixer(&self) -> &M {
+        self.map.mixer()
+    }
+
+    /// Returns the number of elements the set can hold without reallocating.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// An iterator visiting all elements in arbitrary order.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.map.keys(),
+        }
+    }
+
+    /// Returns the number of elements in the set.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Clears the set, returning all elements in an iterator.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            iter: self.map.drain(),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|k, _| f(k));
+    }
+
+    /// Clears the set, removing all values.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the `StoredHashSet`. The collection may reserve more space to avoid
+    /// frequent reallocations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new allocation size overflows `usize`.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be
+    /// inserted in the `StoredHashSet`. The collection may reserve more space
+    /// to avoid frequent reallocations.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional)
+    }
+
+    /// Shrinks the capacity of the set as much as possible. It will drop
+    /// down as much as possible while maintaining the internal rules
+    /// and possibly leaving some space in accordance with the resize policy.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit()
+    }
+
+    /// Shrinks the capacity of the set with a lower limit. It will drop
+    /// down no lower than the supplied limit while maintaining the internal rules
+    /// and possibly leaving some space in accordance with the resize policy.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.map.shrink_to(min_capacity)
+    }
+}
+
+impl<T, M> StoredHashSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+    /// Returns `true` if the set contains a value.
+    ///
+    /// The value may be any type that is [`Equivalent`] to the set's value
+    /// type, such as a borrowed form of it, but [`AutoHash`] on the query
+    /// *must* match that of the value type.
+    ///
+    /// [`AutoHash`]: ../../trait.AutoHash.html
+    /// [`Equivalent`]: ../../trait.Equivalent.html
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the value in the set, if any, that is equal to the given value.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn get<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.get_key_value(value) {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// If the set did not have this value present, `true` is returned.
+    ///
+    /// If the set did have this value present, `false` is returned.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Removes a value from the set. Returns whether the value was
+    /// present in the set.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn remove<Q: ?Sized>(&mut self, value: &Q) -> bool
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes and returns the value in the set, if any, that is equal to the given one.
+    #[cfg_attr(feature = "inline-more", inline)]
+    pub fn take<Q: ?Sized>(&mut self, value: &Q) -> Option<T>
+    where
+        Q: AutoHash + Equivalent<T>,
+    {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.map.remove_entry(value) {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+}
+
+impl<T, M> PartialEq for StoredHashSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+    /// Compares the sets by their stored hashes, like `StoredHashMap`.
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T, M> Eq for StoredHashSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+}
+
+impl<T, M> fmt::Debug for StoredHashSet<T, M>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, M: Default> Default for StoredHashSet<T, M> {
+    /// Creates an empty `StoredHashSet<T, M>` with the `Default` value for the mixer.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn default() -> Self {
+        Self {
+            map: StoredHashMap::default(),
+        }
+    }
+}
+
+impl<T, M> FromIterator<T> for StoredHashSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, M> Extend<T> for StoredHashSet<T, M>
+where
+    T: Eq + AutoHash,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|k| (k, ())));
+    }
+}
+
+impl<'a, T, M> Extend<&'a T> for StoredHashSet<T, M>
+where
+    T: 'a + Eq + AutoHash + Copy,
+    M: HashMixer,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<'a, T, M> IntoIterator for &'a StoredHashSet<T, M> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T, M> IntoIterator for StoredHashSet<T, M> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out
+    /// of the set in arbitrary order. The set cannot be used after calling
+    /// this.
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the items of a `StoredHashSet`.
+///
+/// This `struct` is created by the [`iter`] method on [`StoredHashSet`].
+/// See its documentation for more.
+///
+/// [`StoredHashSet`]: struct.StoredHashSet.html
+/// [`iter`]: struct.StoredHashSet.html#method.iter
+pub struct Iter<'a, K> {
+    iter: Keys<'a, K, ()>,
+}
+
+/// An owning iterator over the items of a `StoredHashSet`.
+///
+/// This `struct` is created by the [`into_iter`] method on [`StoredHashSet`]
+/// (provided by the `IntoIterator` trait). See its documentation for more.
+///
+/// [`StoredHashSet`]: struct.StoredHashSet.html
+/// [`into_iter`]: struct.StoredHashSet.html#method.into_iter
+pub struct IntoIter<K> {
+    iter: map::IntoIter<K, ()>,
+}
+
+/// A draining iterator over the items of a `StoredHashSet`.
+///
+/// This `struct` is created by the [`drain`] method on [`StoredHashSet`].
+/// See its documentation for more.
+///
+/// [`StoredHashSet`]: struct.StoredHashSet.html
+/// [`drain`]: struct.StoredHashSet.html#method.drain
+pub struct Drain<'a, K> {
+    iter: map::Drain<'a, K, ()>,
+}
+
+impl<K> Clone for Iter<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+impl<'a, K> Iterator for Iter<'a, K> {
+    type Item = &'a K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<&'a K> {
+        self.iter.next()
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<K> ExactSizeIterator for Iter<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+impl<K> FusedIterator for Iter<'_, K> {}
+
+impl<K: fmt::Debug> fmt::Debug for Iter<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<K> Iterator for IntoIter<K> {
+    type Item = K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.iter.next() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<K> ExactSizeIterator for IntoIter<K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+impl<K> FusedIterator for IntoIter<K> {}
+
+impl<K: fmt::Debug> fmt::Debug for IntoIter<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries_iter = self.iter.iter().map(|(k, _)| k);
+        f.debug_list().entries(entries_iter).finish()
+    }
+}
+
+impl<K> Iterator for Drain<'_, K> {
+    type Item = K;
+
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn next(&mut self) -> Option<K> {
+        // Avoid `Option::map` because it bloats LLVM IR.
+        match self.iter.next() {
+            Some((k, _)) => Some(k),
+            None => None,
+        }
+    }
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<K> ExactSizeIterator for Drain<'_, K> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+impl<K> FusedIterator for Drain<'_, K> {}
+
+impl<K: fmt::Debug> fmt::Debug for Drain<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries_iter = self.iter.iter().map(|(k, _)| k);
+        f.debug_list().entries(entries_iter).finish()
+    }
+}
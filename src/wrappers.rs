@@ -1,15 +1,34 @@
 //! Simple wrappers that implement `AutoHash`.
+//!
+//! Each wrapper derefs to the value it wraps, and also implements `AsRef`,
+//! `Display`, `PartialOrd`, and `Ord` by forwarding to that value, except that
+//! `ByAddress` orders by address to be consistent with its `Eq`. Their `Hash`
+//! implementations write the `AutoHash` value with `write_u64`, so they can be
+//! used in a standard `HashMap` too.
+//!
+//! The wrappers do *not* implement `Borrow` for their inner value. `Borrow`
+//! requires that a borrowed value hashes and compares the same as its owner,
+//! but a wrapper's `AutoHash` is usually different from any `AutoHash` of the
+//! value itself, so `Borrow` would let the blanket `Equivalent` impl make
+//! lookups that silently miss. Use an `Equivalent` query instead, like
+//! `AutoHashed<&Q, H>` for the hashing wrappers.
 
-use crate::AutoHash;
+use crate::{mix, AutoHash, AutoHash128, Equivalent};
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
+use core::ops::Deref;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{self, AtomicU64};
 
 /// A wrapper for an existing `u64` hash value.
 ///
 /// This is a simple example of a key type that knows its own hash value,
 /// without implementing the `Hash` trait at all.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct U64Hash(pub u64);
 
@@ -27,6 +46,113 @@ impl fmt::Debug for U64Hash {
     }
 }
 
+impl U64Hash {
+    /// Returns the wrapped hash value.
+    #[inline]
+    pub const fn into_inner(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for U64Hash {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for U64Hash {
+    type Target = u64;
+
+    #[inline]
+    fn deref(&self) -> &u64 {
+        &self.0
+    }
+}
+
+impl AsRef<u64> for U64Hash {
+    #[inline]
+    fn as_ref(&self) -> &u64 {
+        &self.0
+    }
+}
+
+impl Hash for U64Hash {
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.get_hash());
+    }
+}
+
+/// A wrapper for an existing `u128` hash value, like a content digest.
+///
+/// Its 64-bit `AutoHash` is the [`fold128`] of the full value, and it also
+/// implements `AutoHash128` for lookups that compare all 128 bits.
+///
+/// [`fold128`]: ../mix/fn.fold128.html
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct U128Hash(pub u128);
+
+impl AutoHash for U128Hash {
+    #[inline]
+    fn get_hash(&self) -> u64 {
+        mix::fold128(self.0)
+    }
+}
+
+impl AutoHash128 for U128Hash {
+    #[inline]
+    fn get_hash128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Debug for U128Hash {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl U128Hash {
+    /// Returns the wrapped hash value.
+    #[inline]
+    pub const fn into_inner(self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for U128Hash {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Deref for U128Hash {
+    type Target = u128;
+
+    #[inline]
+    fn deref(&self) -> &u128 {
+        &self.0
+    }
+}
+
+impl AsRef<u128> for U128Hash {
+    #[inline]
+    fn as_ref(&self) -> &u128 {
+        &self.0
+    }
+}
+
+impl Hash for U128Hash {
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.get_hash());
+    }
+}
+
 /// A wrapper that automatically uses a default hasher.
 ///
 /// Using this with `AutoHashMap`/`Set` is effectively the same as a normal
@@ -92,6 +218,24 @@ where
     }
 }
 
+impl<T, H> Hash for AutoHashed<T, H>
+where
+    T: Hash,
+    H: Hasher + Default,
+{
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.get_hash());
+    }
+}
+
+impl<T: Default, H> Default for AutoHashed<T, H> {
+    #[inline]
+    fn default() -> Self {
+        Self::from(T::default())
+    }
+}
+
 /// A wrapper that caches the hash using a default hasher.
 ///
 /// Using this with `AutoHashMap`/`Set` is similar to a normal `HashMap`/`Set`
@@ -164,6 +308,309 @@ impl<T, H> AutoHash for MemoHashed<T, H> {
     }
 }
 
+impl<T, H> Hash for MemoHashed<T, H> {
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl<T, H> Default for MemoHashed<T, H>
+where
+    T: Hash + Default,
+    H: Hasher + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::from(T::default())
+    }
+}
+
+/// Look up a `MemoHashed<T, H>` key with an `AutoHashed<&Q, H>` query, which
+/// computes the same hash from a borrowed value without allocating a new key.
+///
+/// # Examples
+///
+/// ```
+/// use autohash::AutoHashMap;
+/// use autohash::wrappers::{AutoHashed, MemoHashed};
+/// use std::collections::hash_map::DefaultHasher;
+///
+/// let mut map = AutoHashMap::new();
+/// let key: MemoHashed<String, DefaultHasher> = "key".to_string().into();
+/// map.insert(key, 1);
+///
+/// let query: AutoHashed<&str, DefaultHasher> = "key".into();
+/// assert_eq!(map.get(&query), Some(&1));
+/// ```
+impl<Q, T, H> Equivalent<MemoHashed<T, H>> for AutoHashed<&Q, H>
+where
+    Q: ?Sized + Eq,
+    T: Borrow<Q>,
+{
+    #[inline]
+    fn equivalent(&self, key: &MemoHashed<T, H>) -> bool {
+        *self.value == *key.value.borrow()
+    }
+}
+
+/// A wrapper that computes the hash on first use, and caches it.
+///
+/// This is between `AutoHashed` and `MemoHashed`: wrapping a value is free,
+/// like `AutoHashed`, but the hash is only computed once, like `MemoHashed`.
+/// That suits keys that are created often but only occasionally hashed.
+///
+/// The cache is a `Cell`, so this is not `Sync`. See [`SyncLazyHashed`] for a
+/// variant that can be shared between threads.
+///
+/// The cached hash is not updated if `value` is modified after it was first
+/// hashed, just as `MemoHashed` keeps its original hash.
+///
+/// [`SyncLazyHashed`]: struct.SyncLazyHashed.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::AutoHashMap;
+/// use autohash::wrappers::LazyHashed;
+/// use std::collections::hash_map::DefaultHasher;
+///
+/// let mut map = AutoHashMap::new();
+/// let key: LazyHashed<String, DefaultHasher> = "key".to_string().into();
+/// map.insert(key, 1);
+///
+/// let query: LazyHashed<String, DefaultHasher> = "key".to_string().into();
+/// assert_eq!(map.get(&query), Some(&1));
+/// ```
+pub struct LazyHashed<T, H> {
+    /// The wrapped value
+    pub value: T,
+    hash: Cell<Option<u64>>,
+    hasher: PhantomData<H>,
+}
+
+impl<T, H> From<T> for LazyHashed<T, H> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self {
+            value,
+            hash: Cell::new(None),
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone, H> Clone for LazyHashed<T, H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            hash: self.hash.clone(),
+            hasher: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &Self) {
+        self.value.clone_from(&other.value);
+        self.hash.set(other.hash.get());
+    }
+}
+
+impl<T: fmt::Debug, H> fmt::Debug for LazyHashed<T, H> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: PartialEq, H> PartialEq for LazyHashed<T, H> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, H> Eq for LazyHashed<T, H> {}
+
+impl<T, H> AutoHash for LazyHashed<T, H>
+where
+    T: Hash,
+    H: Hasher + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn get_hash(&self) -> u64 {
+        if let Some(hash) = self.hash.get() {
+            return hash;
+        }
+        let hasher = &mut H::default();
+        self.value.hash(hasher);
+        let hash = hasher.finish();
+        self.hash.set(Some(hash));
+        hash
+    }
+}
+
+impl<T, H> Hash for LazyHashed<T, H>
+where
+    T: Hash,
+    H: Hasher + Default,
+{
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.get_hash());
+    }
+}
+
+impl<T: Default, H> Default for LazyHashed<T, H> {
+    #[inline]
+    fn default() -> Self {
+        Self::from(T::default())
+    }
+}
+
+/// Look up a `LazyHashed<T, H>` key with an `AutoHashed<&Q, H>` query, which
+/// computes the same hash from a borrowed value without allocating a new key.
+impl<Q, T, H> Equivalent<LazyHashed<T, H>> for AutoHashed<&Q, H>
+where
+    Q: ?Sized + Eq,
+    T: Borrow<Q>,
+{
+    #[inline]
+    fn equivalent(&self, key: &LazyHashed<T, H>) -> bool {
+        *self.value == *key.value.borrow()
+    }
+}
+
+/// A thread-safe wrapper that computes the hash on first use, and caches it.
+///
+/// This is like [`LazyHashed`], but the cache is an `AtomicU64`, so it can be
+/// shared between threads. A cached value of 0 means the hash has not been
+/// computed yet, so a value whose hash really is 0 will be hashed again on
+/// every call. Threads that race on the first call may each compute the hash,
+/// but they will all store the same result.
+///
+/// This is only available on targets with 64-bit atomics.
+///
+/// [`LazyHashed`]: struct.LazyHashed.html
+#[cfg(target_has_atomic = "64")]
+pub struct SyncLazyHashed<T, H> {
+    /// The wrapped value
+    pub value: T,
+    hash: AtomicU64,
+    hasher: PhantomData<H>,
+}
+
+/// The `SyncLazyHashed` cache value for a hash that isn't computed yet.
+#[cfg(target_has_atomic = "64")]
+const UNHASHED: u64 = 0;
+
+#[cfg(target_has_atomic = "64")]
+impl<T, H> From<T> for SyncLazyHashed<T, H> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self {
+            value,
+            hash: AtomicU64::new(UNHASHED),
+            hasher: PhantomData,
+        }
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: Clone, H> Clone for SyncLazyHashed<T, H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            hash: AtomicU64::new(self.hash.load(atomic::Ordering::Relaxed)),
+            hasher: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &Self) {
+        self.value.clone_from(&other.value);
+        *self.hash.get_mut() = other.hash.load(atomic::Ordering::Relaxed);
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: fmt::Debug, H> fmt::Debug for SyncLazyHashed<T, H> {
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: PartialEq, H> PartialEq for SyncLazyHashed<T, H> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: Eq, H> Eq for SyncLazyHashed<T, H> {}
+
+#[cfg(target_has_atomic = "64")]
+impl<T, H> AutoHash for SyncLazyHashed<T, H>
+where
+    T: Hash,
+    H: Hasher + Default,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn get_hash(&self) -> u64 {
+        // The hash is a pure function of the value, so relaxed ordering is
+        // enough -- any thread that sees a cached hash would compute the same.
+        let hash = self.hash.load(atomic::Ordering::Relaxed);
+        if hash != UNHASHED {
+            return hash;
+        }
+        let hasher = &mut H::default();
+        self.value.hash(hasher);
+        let hash = hasher.finish();
+        self.hash.store(hash, atomic::Ordering::Relaxed);
+        hash
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T, H> Hash for SyncLazyHashed<T, H>
+where
+    T: Hash,
+    H: Hasher + Default,
+{
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.get_hash());
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl<T: Default, H> Default for SyncLazyHashed<T, H> {
+    #[inline]
+    fn default() -> Self {
+        Self::from(T::default())
+    }
+}
+
+/// Look up a `SyncLazyHashed<T, H>` key with an `AutoHashed<&Q, H>` query,
+/// which computes the same hash from a borrowed value without allocating a
+/// new key.
+#[cfg(target_has_atomic = "64")]
+impl<Q, T, H> Equivalent<SyncLazyHashed<T, H>> for AutoHashed<&Q, H>
+where
+    Q: ?Sized + Eq,
+    T: Borrow<Q>,
+{
+    #[inline]
+    fn equivalent(&self, key: &SyncLazyHashed<T, H>) -> bool {
+        *self.value == *key.value.borrow()
+    }
+}
+
 /// A wrapper that uses a provided hash.
 ///
 /// The raw hash is still required to uphold the contract that if two values are
@@ -221,3 +668,213 @@ impl<T> AutoHash for RawHashed<T> {
         self.hash
     }
 }
+
+impl<T> Hash for RawHashed<T> {
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// Creates the default value with a raw hash of 0.
+impl<T: Default> Default for RawHashed<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0, T::default())
+    }
+}
+
+// The value wrappers forward these traits to their inner value.
+macro_rules! impl_value_traits {
+    ($($name:ident<T $(, $H:ident)?>)*) => {$(
+        impl<T $(, $H)?> $name<T $(, $H)?> {
+            /// Unwraps the inner value.
+            #[inline]
+            pub fn into_inner(self) -> T {
+                self.value
+            }
+        }
+
+        impl<T: fmt::Display $(, $H)?> fmt::Display for $name<T $(, $H)?> {
+            #[cfg_attr(feature = "inline-more", inline)]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.value.fmt(f)
+            }
+        }
+
+        impl<T $(, $H)?> Deref for $name<T $(, $H)?> {
+            type Target = T;
+
+            #[inline]
+            fn deref(&self) -> &T {
+                &self.value
+            }
+        }
+
+        impl<T $(, $H)?> AsRef<T> for $name<T $(, $H)?> {
+            #[inline]
+            fn as_ref(&self) -> &T {
+                &self.value
+            }
+        }
+
+        impl<T: PartialOrd $(, $H)?> PartialOrd for $name<T $(, $H)?> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.value.partial_cmp(&other.value)
+            }
+        }
+
+        impl<T: Ord $(, $H)?> Ord for $name<T $(, $H)?> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.value.cmp(&other.value)
+            }
+        }
+    )*}
+}
+
+impl_value_traits! {
+    AutoHashed<T, H>
+    MemoHashed<T, H>
+    LazyHashed<T, H>
+    RawHashed<T>
+}
+
+#[cfg(target_has_atomic = "64")]
+impl_value_traits! {
+    SyncLazyHashed<T, H>
+}
+
+/// A wrapper for a pointer that is hashed and compared by its address.
+///
+/// The `AutoHash` implementations for `&T`, `Rc<T>`, and `Arc<T>` forward to
+/// the value they point to, as does their `Eq`. `ByAddress` instead uses the
+/// identity of the pointee, so two separately allocated but equal values are
+/// different keys, while clones of the same `Rc` or `Arc` are the same key.
+///
+/// The hash is the address mixed with [`mix64`], since addresses are aligned
+/// and often close together. Only the address is used, not any pointer
+/// metadata like a slice length or a trait object's vtable. Distinct values of
+/// a zero-sized type may share the same address.
+///
+/// [`mix64`]: ../mix/fn.mix64.html
+///
+/// # Examples
+///
+/// ```
+/// use autohash::AutoHashSet;
+/// use autohash::wrappers::ByAddress;
+/// use std::rc::Rc;
+///
+/// let a = Rc::new("node");
+/// let b = Rc::new("node");
+///
+/// let mut set = AutoHashSet::new();
+/// set.insert(ByAddress(Rc::clone(&a)));
+/// assert!(set.contains(&ByAddress(Rc::clone(&a))));
+/// assert!(!set.contains(&ByAddress(b)));
+/// ```
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct ByAddress<P>(pub P);
+
+impl<P: Deref> ByAddress<P> {
+    /// Returns the address of the pointee.
+    #[inline]
+    fn addr(&self) -> *const () {
+        &*self.0 as *const P::Target as *const ()
+    }
+}
+
+impl<P> fmt::Debug for ByAddress<P>
+where
+    P: Deref,
+    P::Target: fmt::Debug,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<P: Deref> PartialEq for ByAddress<P> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl<P: Deref> Eq for ByAddress<P> {}
+
+impl<P: Deref> AutoHash for ByAddress<P> {
+    #[inline]
+    fn get_hash(&self) -> u64 {
+        mix::mix64(self.addr() as usize as u64)
+    }
+}
+
+impl<P> ByAddress<P> {
+    /// Unwraps the inner pointer.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P> fmt::Display for ByAddress<P>
+where
+    P: Deref,
+    P::Target: fmt::Display,
+{
+    #[cfg_attr(feature = "inline-more", inline)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<P> Deref for ByAddress<P> {
+    type Target = P;
+
+    #[inline]
+    fn deref(&self) -> &P {
+        &self.0
+    }
+}
+
+impl<P> AsRef<P> for ByAddress<P> {
+    #[inline]
+    fn as_ref(&self) -> &P {
+        &self.0
+    }
+}
+
+/// Orders by address, to be consistent with `Eq`.
+impl<P: Deref> PartialOrd for ByAddress<P> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders by address, to be consistent with `Eq`.
+impl<P: Deref> Ord for ByAddress<P> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl<P: Deref> Hash for ByAddress<P> {
+    #[inline]
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.get_hash());
+    }
+}
+
+impl<P: Default> Default for ByAddress<P> {
+    #[inline]
+    fn default() -> Self {
+        ByAddress(P::default())
+    }
+}
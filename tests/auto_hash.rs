@@ -0,0 +1,56 @@
+//! Sanity check the built-in `AutoHash` implementations.
+
+use autohash::mix::{combine, mix64};
+use autohash::{AutoHash, AutoHashMap, AutoHashSet};
+
+#[test]
+fn integers() {
+    assert_eq!(0u8.get_hash(), 0);
+    assert_eq!(1u32.get_hash(), mix64(1));
+    assert_eq!(1u64.get_hash(), 1usize.get_hash());
+    assert_eq!((-1i8).get_hash(), (-1i64).get_hash());
+    assert_eq!(u128::from(u64::MAX).get_hash(), mix64(u64::MAX));
+    assert_ne!((1u128 << 64).get_hash(), 1u128.get_hash());
+    assert_eq!(true.get_hash(), mix64(1));
+    assert_eq!('a'.get_hash(), mix64('a' as u64));
+}
+
+#[test]
+fn high_bits_are_mixed() {
+    // hashbrown takes its control bytes from the top 7 bits.
+    let tags: AutoHashSet<u64> = (0..1000u64).map(|i| i.get_hash() >> 57).collect();
+    assert_eq!(tags.len(), 128);
+}
+
+#[test]
+fn tuples_and_arrays() {
+    assert_eq!(().get_hash(), 0);
+    assert_eq!((1u8,).get_hash(), combine(0, 1u8.get_hash()));
+    assert_eq!(
+        (1u8, 'x', false).get_hash(),
+        combine(combine(combine(0, 1u8.get_hash()), 'x'.get_hash()), 0)
+    );
+    assert_ne!((1u32, 2u32).get_hash(), (2u32, 1u32).get_hash());
+
+    let array = [3u16, 4, 5];
+    assert_eq!(array.get_hash(), (3u16, 4u16, 5u16).get_hash());
+    assert_eq!([0u8; 16].get_hash(), [0u8; 16].get_hash());
+    assert_eq!(<[u8; 0]>::get_hash(&[]), 0);
+}
+
+#[test]
+fn primitive_keys() {
+    let mut map = AutoHashMap::new();
+    for i in 0..100u64 {
+        map.insert(i, i * 2);
+    }
+    assert_eq!(map.len(), 100);
+    assert_eq!(map[&42], 84);
+    assert_eq!(map.get(&100), None);
+
+    let mut pairs = AutoHashMap::new();
+    pairs.insert((1u32, 2u32), "a");
+    pairs.insert((2u32, 1u32), "b");
+    assert_eq!(pairs[&(1, 2)], "a");
+    assert_eq!(pairs[&(2, 1)], "b");
+}
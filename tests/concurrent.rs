@@ -0,0 +1,155 @@
+//! Check the sharded concurrent map from several threads.
+
+#![cfg(feature = "std")]
+
+use autohash::concurrent::{ReadMostlyMap, ShardedAutoHashMap};
+use autohash::mix::Murmur3Mix;
+use autohash::wrappers::U64Hash;
+use autohash::AutoHashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn basic() {
+    let map = ShardedAutoHashMap::with_shards(3);
+    assert_eq!(map.shard_count(), 4);
+    assert!(map.is_empty());
+
+    assert_eq!(map.insert(1, "a"), None);
+    assert_eq!(map.insert(1, "b"), Some("a"));
+    assert_eq!(map.insert(2, "c"), None);
+    assert!(map.contains_key(&1));
+    assert_eq!(map.get(&1, |v| v.len()), Some(1));
+    assert_eq!(map.get_mut(&2, |v| std::mem::replace(v, "d")), Some("c"));
+    assert_eq!(map.get(&3, |_| ()), None);
+    assert_eq!(map.len(), 2);
+
+    assert_eq!(map.remove(&1), Some("b"));
+    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.snapshot().collect::<Vec<_>>(), [(2, "d")]);
+
+    map.clear();
+    assert!(map.is_empty());
+}
+
+#[test]
+fn threads() {
+    let map = Arc::new(ShardedAutoHashMap::<U64Hash, u64, Murmur3Mix>::default());
+    let threads: Vec<_> = (0..8)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    map.entry(U64Hash(i), |entry| *entry.or_insert(0) += t);
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert_eq!(map.len(), 1000);
+    let mut sum = 0;
+    map.for_each(|_, v| sum += v);
+    assert_eq!(sum, 1000 * 28);
+
+    // Sequential keys should spread over all the shards.
+    let shards = Arc::try_unwrap(map).unwrap().into_shards();
+    assert_eq!(shards.len(), 16);
+    assert!(shards.iter().all(|shard| shard.len() > 20));
+}
+
+#[test]
+fn retain() {
+    let map = ShardedAutoHashMap::new();
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+    map.retain(|&k, v| {
+        *v *= 2;
+        k % 2 == 0
+    });
+    assert_eq!(map.len(), 50);
+    let mut entries: Vec<_> = map.snapshot().collect();
+    entries.sort();
+    assert_eq!(entries, (0..50).map(|i| (2 * i, 4 * i)).collect::<Vec<_>>());
+}
+
+#[test]
+fn read_mostly() {
+    let map: ReadMostlyMap<u32, String> = (0..10)
+        .map(|i| (i, i.to_string()))
+        .collect::<AutoHashMap<_, _>>()
+        .into();
+    assert_eq!(map.len(), 10);
+
+    let old = map.read();
+    let len = map.update(|m| {
+        m.remove(&0);
+        m.insert(10, "10".to_string());
+        m.len()
+    });
+    assert_eq!(len, 10);
+    assert!(old.contains_key(&0));
+    assert!(!old.contains_key(&10));
+    drop(old);
+
+    let new = map.read();
+    assert!(!new.contains_key(&0));
+    assert_eq!(new.get(&10).map(String::as_str), Some("10"));
+    drop(new);
+
+    // A panicking update doesn't publish anything.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        map.update(|m| {
+            m.clear();
+            panic!("oops");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(map.len(), 10);
+
+    map.publish(AutoHashMap::new());
+    assert!(map.is_empty());
+    map.update(|m| m.insert(1, "one".to_string()));
+    assert_eq!(map.into_inner().get(&1).map(String::as_str), Some("one"));
+}
+
+#[test]
+fn read_mostly_threads() {
+    // Every published table has `len` entries, all with the value `len`.
+    let map = Arc::new(ReadMostlyMap::new());
+    map.update(|m| m.insert(0, 1));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let map = Arc::clone(&map);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    let table = map.read();
+                    let len = table.len();
+                    assert!(table.values().all(|&v| v == len));
+                }
+            })
+        })
+        .collect();
+
+    for len in 2..200 {
+        map.update(|m| {
+            m.insert(len - 1, len);
+            for v in m.values_mut() {
+                *v = len;
+            }
+        });
+    }
+    done.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(map.len(), 199);
+}
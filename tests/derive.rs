@@ -0,0 +1,127 @@
+#![cfg(feature = "derive")]
+
+use autohash::mix::combine;
+use autohash::wrappers::U64Hash;
+use autohash::{AutoHash, AutoHashMap};
+
+#[derive(AutoHash, PartialEq, Eq)]
+struct Named {
+    a: U64Hash,
+    b: U64Hash,
+}
+
+#[derive(AutoHash, PartialEq, Eq)]
+struct Tuple(U64Hash, U64Hash);
+
+#[derive(AutoHash)]
+struct Unit;
+
+#[derive(AutoHash, PartialEq, Eq)]
+enum Enum {
+    A,
+    B(U64Hash),
+    C { x: U64Hash, y: U64Hash },
+}
+
+#[derive(AutoHash)]
+struct Generic<T> {
+    inner: T,
+}
+
+fn len_hash(s: &str) -> u64 {
+    s.len() as u64
+}
+
+#[derive(AutoHash, PartialEq, Eq)]
+struct Attrs {
+    id: U64Hash,
+    #[autohash(skip)]
+    #[allow(dead_code)]
+    cache: u8,
+    #[autohash(with = len_hash)]
+    name: String,
+}
+
+#[derive(AutoHash)]
+enum Empty {}
+
+#[test]
+fn struct_fields_in_order() {
+    let named = Named {
+        a: U64Hash(1),
+        b: U64Hash(2),
+    };
+    assert_eq!(named.get_hash(), combine(combine(0, 1), 2));
+
+    let tuple = Tuple(U64Hash(1), U64Hash(2));
+    assert_eq!(tuple.get_hash(), named.get_hash());
+    assert_ne!(tuple.get_hash(), Tuple(U64Hash(2), U64Hash(1)).get_hash());
+
+    assert_eq!(Unit.get_hash(), 0);
+}
+
+#[test]
+fn enum_variants() {
+    assert_eq!(Enum::A.get_hash(), combine(0, 0));
+    assert_eq!(Enum::B(U64Hash(5)).get_hash(), combine(combine(0, 1), 5));
+    assert_eq!(
+        Enum::C {
+            x: U64Hash(5),
+            y: U64Hash(6)
+        }
+        .get_hash(),
+        combine(combine(combine(0, 2), 5), 6)
+    );
+    assert_ne!(
+        Enum::B(U64Hash(0)).get_hash(),
+        Enum::C {
+            x: U64Hash(0),
+            y: U64Hash(0)
+        }
+        .get_hash()
+    );
+
+    fn unreachable(empty: &Empty) -> u64 {
+        empty.get_hash()
+    }
+    let _ = unreachable;
+}
+
+#[test]
+fn generic_bounds() {
+    let g = Generic { inner: U64Hash(7) };
+    assert_eq!(g.get_hash(), combine(0, 7));
+}
+
+#[test]
+fn field_attributes() {
+    let a = Attrs {
+        id: U64Hash(1),
+        cache: 0,
+        name: "abc".into(),
+    };
+    let b = Attrs {
+        id: U64Hash(1),
+        cache: 255,
+        name: "xyz".into(),
+    };
+    assert_eq!(a.get_hash(), combine(combine(0, 1), 3));
+    assert_eq!(a.get_hash(), b.get_hash());
+}
+
+#[test]
+fn map_keys() {
+    let mut map = AutoHashMap::new();
+    map.insert(Enum::A, "a");
+    map.insert(Enum::B(U64Hash(1)), "b");
+    map.insert(
+        Enum::C {
+            x: U64Hash(1),
+            y: U64Hash(2),
+        },
+        "c",
+    );
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[&Enum::B(U64Hash(1))], "b");
+    assert!(!map.contains_key(&Enum::B(U64Hash(2))));
+}
@@ -0,0 +1,116 @@
+//! Check that `FloodGuardMap` moves flooded hashes to its overflow map.
+
+use autohash::flood::{FloodGuardMap, DEFAULT_THRESHOLD};
+use autohash::mix::{KeyedMix, NoMix};
+use autohash::AutoHash;
+
+/// A key with a chosen hash, and ordered by its id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    hash: u64,
+    id: u32,
+}
+
+impl AutoHash for Key {
+    fn get_hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+fn key(hash: u64, id: u32) -> Key {
+    Key { hash, id }
+}
+
+#[test]
+fn no_flood() {
+    let mut map: FloodGuardMap<_, _> = (0..1000)
+        .map(|i| (key(i.get_hash(), i as u32), i))
+        .collect();
+    assert_eq!(map.len(), 1000);
+    assert_eq!(map.overflow_len(), 0);
+    assert_eq!(map.flooded_hashes(), 0);
+
+    assert_eq!(map.insert(key(5u64.get_hash(), 5), 50), Some(5));
+    assert_eq!(map.get(&key(5u64.get_hash(), 5)), Some(&50));
+    assert_eq!(map.remove(&key(5u64.get_hash(), 5)), Some(50));
+    assert!(!map.contains_key(&key(5u64.get_hash(), 5)));
+    assert_eq!(map.len(), 999);
+}
+
+#[test]
+fn flood() {
+    let mut map = FloodGuardMap::with_mixer(KeyedMix::new(0));
+
+    // Mix some innocent keys around the flood.
+    for i in 0..1000 {
+        map.insert(key(u64::from(i).get_hash(), i), i);
+        map.insert(key(42, i), i);
+    }
+    assert_eq!(map.len(), 2000);
+    assert_eq!(map.flooded_hashes(), 1);
+    assert_eq!(map.overflow_len(), 1000);
+
+    for i in 0..1000 {
+        assert_eq!(map.get(&key(u64::from(i).get_hash(), i)), Some(&i));
+        assert_eq!(map.get(&key(42, i)), Some(&i));
+    }
+    assert_eq!(map.get(&key(42, 1000)), None);
+
+    // Flooded keys still update in place.
+    *map.get_mut(&key(42, 7)).unwrap() = 70;
+    assert_eq!(map.insert(key(42, 7), 700), Some(70));
+    assert_eq!(map.remove(&key(42, 7)), Some(700));
+    assert_eq!(map.len(), 1999);
+    assert_eq!(map.iter().count(), 1999);
+
+    let clone = map.clone();
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.flooded_hashes(), 0);
+    assert_eq!(clone.len(), 1999);
+}
+
+#[test]
+fn threshold() {
+    let mut map = FloodGuardMap::with_threshold_and_mixer(2, NoMix);
+    assert_eq!(map.threshold(), 2);
+    for i in 0..3 {
+        map.insert(key(0, i), ());
+    }
+    assert_eq!(map.overflow_len(), 0);
+    map.insert(key(0, 3), ());
+    assert_eq!(map.overflow_len(), 4);
+
+    let default: FloodGuardMap<Key, ()> = FloodGuardMap::new();
+    assert_eq!(default.threshold(), DEFAULT_THRESHOLD);
+}
+
+#[test]
+fn lookup_flood() {
+    // Inserting threshold + 1 keys compares at most threshold keys each time,
+    // but looking up another key with the same hash compares them all.
+    let mut map = FloodGuardMap::with_threshold_and_mixer(2, NoMix);
+    for i in 0..3 {
+        map.insert(key(0, i), i);
+    }
+    assert_eq!(map.overflow_len(), 0);
+
+    // A shared lookup can only note the flood...
+    assert!(!map.contains_key(&key(0, 99)));
+    assert_eq!(map.flooded_hashes(), 0);
+
+    // ... and the next mutable call moves it.
+    assert_eq!(map.remove(&key(0, 99)), None);
+    assert_eq!(map.flooded_hashes(), 1);
+    assert_eq!(map.overflow_len(), 3);
+    assert_eq!(map.get(&key(0, 2)), Some(&2));
+
+    let mut map = FloodGuardMap::with_threshold_and_mixer(2, NoMix);
+    for i in 0..3 {
+        map.insert(key(0, i), i);
+    }
+    assert_eq!(map.get_mut(&key(0, 99)), None);
+    assert_eq!(map.flooded_hashes(), 1);
+    *map.get_mut(&key(0, 1)).unwrap() = 10;
+    assert_eq!(map.get(&key(0, 1)), Some(&10));
+}
@@ -0,0 +1,193 @@
+//! Check the insertion-ordered map and set, and the positions of their keys.
+
+use autohash::index::map::Entry;
+use autohash::index::{AutoIndexMap, AutoIndexSet};
+use autohash::mix::{HashMixer, Murmur3Mix};
+use autohash::AutoHash;
+
+/// A key with a given hash, so several keys can share one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Hashed(u64, u32);
+
+impl AutoHash for Hashed {
+    fn get_hash(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Asserts that every key in the map finds its own position.
+fn assert_indexed<M: HashMixer>(map: &AutoIndexMap<Hashed, u32, M>) {
+    for (i, (k, v)) in map.iter().enumerate() {
+        assert_eq!(map.get_full(k), Some((i, k, v)));
+    }
+}
+
+fn keys<M>(map: &AutoIndexMap<Hashed, u32, M>) -> Vec<u32> {
+    map.keys().map(|k| k.1).collect()
+}
+
+#[test]
+fn swap_remove_shared_hash() {
+    let mut map: AutoIndexMap<Hashed, u32> = (0..6).map(|i| (Hashed(0, i), i)).collect();
+
+    // The last entry moves into the removed one's place.
+    assert_eq!(
+        map.swap_remove_full(&Hashed(0, 1)),
+        Some((1, Hashed(0, 1), 1))
+    );
+    assert_eq!(keys(&map), [0, 5, 2, 3, 4]);
+    assert_eq!(map.get_index_of(&Hashed(0, 5)), Some(1));
+    assert_indexed(&map);
+
+    assert_eq!(map.swap_remove(&Hashed(0, 4)), Some(4));
+    assert_eq!(keys(&map), [0, 5, 2, 3]);
+    assert_eq!(map.swap_remove(&Hashed(0, 4)), None);
+    assert_indexed(&map);
+}
+
+#[test]
+fn shift_remove_shared_hash() {
+    let mut map = AutoIndexMap::with_mixer(Murmur3Mix);
+    map.extend((0..6).map(|i| (Hashed(u64::from(i % 2), i), i)));
+
+    // Every later entry moves down by one.
+    assert_eq!(
+        map.shift_remove_full(&Hashed(0, 2)),
+        Some((2, Hashed(0, 2), 2))
+    );
+    assert_eq!(keys(&map), [0, 1, 3, 4, 5]);
+    assert_eq!(map.get_index_of(&Hashed(0, 4)), Some(3));
+    assert_eq!(map.get_index_of(&Hashed(1, 5)), Some(4));
+    assert_indexed(&map);
+
+    assert_eq!(map.shift_remove(&Hashed(0, 0)), Some(0));
+    assert_eq!(keys(&map), [1, 3, 4, 5]);
+    assert_indexed(&map);
+}
+
+#[test]
+fn sort_by_then_index() {
+    let mut map: AutoIndexMap<Hashed, u32> = (0..10)
+        .map(|i| (Hashed(u64::from(i % 3), i), 9 - i))
+        .collect();
+    map.sort_by(|_, v1, _, v2| v1.cmp(v2));
+    assert_eq!(keys(&map), [9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(map.get_index_of(&Hashed(0, 9)), Some(0));
+    assert_eq!(map.get_index_of(&Hashed(0, 0)), Some(9));
+    assert_eq!(map.get_index_of(&Hashed(1, 4)), Some(5));
+    assert_indexed(&map);
+}
+
+#[test]
+fn entry_index() {
+    let mut map: AutoIndexMap<Hashed, u32> = (0..4).map(|i| (Hashed(0, i), i)).collect();
+    match map.entry(Hashed(0, 2)) {
+        Entry::Occupied(entry) => assert_eq!(entry.index(), 2),
+        Entry::Vacant(_) => panic!("2 should be occupied"),
+    }
+    match map.entry(Hashed(0, 9)) {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.index(), 4);
+            entry.insert(9);
+        }
+        Entry::Occupied(_) => panic!("9 should be vacant"),
+    }
+    assert_eq!(map.entry(Hashed(0, 9)).index(), 4);
+    assert_eq!(map.entry(Hashed(0, 0)).index(), 0);
+}
+
+#[test]
+fn retain() {
+    let mut map: AutoIndexMap<Hashed, u32> =
+        (0..10).map(|i| (Hashed(u64::from(i % 2), i), i)).collect();
+    map.retain(|k, v| {
+        *v += 1;
+        k.1 % 3 != 0
+    });
+    assert_eq!(keys(&map), [1, 2, 4, 5, 7, 8]);
+    assert_eq!(map[&Hashed(1, 7)], 8);
+    assert_indexed(&map);
+}
+
+#[test]
+fn retain_panic() {
+    let mut map: AutoIndexMap<Hashed, u32> =
+        (0..10).map(|i| (Hashed(u64::from(i % 2), i), i)).collect();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.retain(|k, _| {
+            assert_ne!(k.1, 5);
+            k.1 % 2 == 1
+        });
+    }));
+    assert!(result.is_err());
+
+    // The removed entries stay removed, and the rest keep their order.
+    assert_eq!(keys(&map), [1, 3, 5, 6, 7, 8, 9]);
+    assert_indexed(&map);
+}
+
+#[test]
+fn map_entry() {
+    let mut map = AutoIndexMap::new();
+    for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+        *map.entry(word.len()).or_insert(0) += 1;
+    }
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), [3, 5, 4]);
+    assert_eq!(map.values().copied().collect::<Vec<_>>(), [4, 3, 2]);
+
+    match map.entry(5) {
+        Entry::Occupied(entry) => {
+            assert_eq!(entry.index(), 1);
+            assert_eq!(entry.shift_remove_entry(), (5, 3));
+        }
+        Entry::Vacant(_) => panic!("5 should be occupied"),
+    }
+    match map.entry(6) {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.index(), 2);
+            assert_eq!(*entry.insert(7), 7);
+        }
+        Entry::Occupied(_) => panic!("6 should be vacant"),
+    }
+    assert_eq!(map.get_index(1), Some((&4, &2)));
+
+    let entry = map.entry(3).and_modify(|v| *v *= 10).insert(1);
+    assert_eq!(entry.get(), &1);
+    assert_eq!(entry.remove(), 1);
+    assert_eq!(map.get_index(0), Some((&6, &7)));
+    assert_eq!(map.iter().next_back(), Some((&4, &2)));
+}
+
+#[test]
+fn map_sort_and_index() {
+    let mut map: AutoIndexMap<u32, char> = vec![(3, 'c'), (1, 'a'), (2, 'b')].into_iter().collect();
+    map.sort_keys();
+    assert_eq!(map.get_index_of(&1), Some(0));
+    assert_eq!(map[&3], 'c');
+
+    if let Some((_, v)) = map.get_index_mut(2) {
+        *v = 'z';
+    }
+    assert_eq!(map.swap_remove_index(0), Some((1, 'a')));
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [(3, 'z'), (2, 'b')]);
+}
+
+#[test]
+fn set_order() {
+    let mut set: AutoIndexSet<u32> = vec![5, 3, 5, 8, 1].into_iter().collect();
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), [5, 3, 8, 1]);
+    assert_eq!(set.insert_full(3), (1, false));
+    assert_eq!(set.insert_full(9), (4, true));
+
+    assert!(set.swap_remove(&5));
+    assert_eq!(set.get_index(0), Some(&9));
+    assert_eq!(set.shift_take(&3), Some(3));
+    assert_eq!(set.get_full(&1), Some((2, &1)));
+
+    set.sort();
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), [1, 8, 9]);
+    assert_eq!(set.get_index_of(&9), Some(2));
+
+    let other: AutoIndexSet<u32> = vec![9, 1, 8].into_iter().collect();
+    assert_eq!(set, other);
+}
@@ -0,0 +1,124 @@
+//! Check the LRU cache's recency order, eviction, and removal.
+
+use autohash::lru::AutoLruCache;
+use autohash::mix::Murmur3Mix;
+use autohash::AutoHash;
+
+/// A key with a given hash, so several keys can share one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Hashed(u64, u32);
+
+impl AutoHash for Hashed {
+    fn get_hash(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Returns the cache's keys, most recently used first.
+fn order<V, M>(cache: &AutoLruCache<u32, V, M>) -> Vec<u32> {
+    cache.iter().map(|(&k, _)| k).collect()
+}
+
+#[test]
+fn get_moves_to_front() {
+    let mut cache = AutoLruCache::new(4);
+    for i in 1..=3 {
+        cache.put(i, i * 10);
+    }
+    assert_eq!(order(&cache), [3, 2, 1]);
+
+    // Looking without using the entry keeps the order.
+    assert_eq!(cache.peek(&1), Some(&10));
+    assert!(cache.contains_key(&2));
+    assert_eq!(cache.peek(&9), None);
+    assert_eq!(order(&cache), [3, 2, 1]);
+
+    assert_eq!(cache.get(&1), Some(&10));
+    assert_eq!(order(&cache), [1, 3, 2]);
+    *cache.get_mut(&2).unwrap() += 1;
+    assert_eq!(order(&cache), [2, 1, 3]);
+    assert_eq!(cache.peek(&2), Some(&21));
+
+    assert_eq!(cache.get(&9), None);
+    assert_eq!(order(&cache), [2, 1, 3]);
+}
+
+#[test]
+fn put_evicts_lru() {
+    let mut cache = AutoLruCache::with_mixer(3, Murmur3Mix);
+    assert_eq!(cache.put(1, 'a'), None);
+    assert_eq!(cache.put(2, 'b'), None);
+    assert_eq!(cache.put(3, 'c'), None);
+    cache.get(&1);
+    assert_eq!(cache.peek_lru(), Some((&2, &'b')));
+
+    assert_eq!(cache.put(4, 'd'), Some((2, 'b')));
+    assert_eq!(order(&cache), [4, 1, 3]);
+    assert!(!cache.contains_key(&2));
+
+    // Replacing a value returns the old one, and evicts nothing.
+    assert_eq!(cache.put(3, 'C'), Some((3, 'c')));
+    assert_eq!(order(&cache), [3, 4, 1]);
+    assert_eq!(cache.len(), 3);
+}
+
+#[test]
+fn resize_evicts_lru() {
+    let mut cache = AutoLruCache::new(5);
+    for i in 1..=5 {
+        cache.put(i, ());
+    }
+    cache.get(&1);
+    assert_eq!(order(&cache), [1, 5, 4, 3, 2]);
+
+    cache.resize(2);
+    assert_eq!(cache.cap(), 2);
+    assert_eq!(order(&cache), [1, 5]);
+    assert!((2..=4).all(|i| !cache.contains_key(&i)));
+
+    cache.resize(3);
+    assert_eq!(cache.put(6, ()), None);
+    assert_eq!(order(&cache), [6, 1, 5]);
+    assert_eq!(cache.pop_lru(), Some((5, ())));
+}
+
+#[test]
+fn remove_head_and_tail() {
+    let mut cache = AutoLruCache::new(5);
+    for i in 1..=5 {
+        cache.put(Hashed(0, i), i);
+    }
+
+    assert_eq!(cache.remove(&Hashed(0, 5)), Some(5));
+    let rev: Vec<_> = cache.iter().rev().map(|(k, _)| k.1).collect();
+    assert_eq!(rev, [1, 2, 3, 4]);
+
+    assert_eq!(cache.remove_entry(&Hashed(0, 1)), Some((Hashed(0, 1), 1)));
+    let rev: Vec<_> = cache.iter().rev().map(|(k, _)| k.1).collect();
+    assert_eq!(rev, [2, 3, 4]);
+    assert_eq!(cache.peek_lru(), Some((&Hashed(0, 2), &2)));
+
+    // The remaining keys share a hash, and are still found after the moves.
+    assert_eq!(cache.get(&Hashed(0, 3)), Some(&3));
+    assert_eq!(cache.remove(&Hashed(0, 1)), None);
+    let keys: Vec<_> = cache.iter().map(|(k, _)| k.1).collect();
+    assert_eq!(keys, [3, 4, 2]);
+}
+
+#[test]
+fn zero_capacity() {
+    let mut cache = AutoLruCache::new(0);
+    assert_eq!(cache.put('a', 1), Some(('a', 1)));
+    assert!(cache.is_empty());
+    assert_eq!(cache.peek_lru(), None);
+    assert_eq!(cache.pop_lru(), None);
+
+    cache.resize(1);
+    assert_eq!(cache.put('a', 1), None);
+    assert_eq!(cache.put('b', 2), Some(('a', 1)));
+    *cache.get_mut(&'b').unwrap() += 1;
+    assert_eq!(cache.peek(&'b'), Some(&3));
+
+    cache.clear();
+    assert!(!cache.contains_key(&'b'));
+}
@@ -0,0 +1,135 @@
+//! Check that maps and sets apply their `HashMixer` consistently.
+
+use autohash::mix::{FibonacciMix, HashMixer, KeyedMix, Murmur3Mix, NoMix, SeededMix};
+use autohash::wrappers::{RawHashed, U64Hash};
+use autohash::{AutoHash, AutoHashMap, AutoHashSet};
+
+#[test]
+fn mixers() {
+    assert_eq!(NoMix.mix(12345), 12345);
+    assert_eq!(Murmur3Mix.mix(12345), autohash::mix::mix64(12345));
+    assert_ne!(FibonacciMix.mix(1), 1);
+    assert_ne!(SeededMix::new(1).mix(5), SeededMix::new(2).mix(5));
+    assert_eq!(SeededMix::new(7).seed(), 7);
+}
+
+#[test]
+fn sequential_tags() {
+    // hashbrown takes its control bytes from the top 7 bits.
+    fn tags<M: HashMixer>(mixer: M) -> usize {
+        let tags: AutoHashSet<u64> = (0..1000u64).map(|i| mixer.mix(i) >> 57).collect();
+        tags.len()
+    }
+    assert_eq!(tags(NoMix), 1);
+    assert_eq!(tags(Murmur3Mix), 128);
+    assert_eq!(tags(SeededMix::new(42)), 128);
+    assert!(tags(FibonacciMix) > 100);
+}
+
+fn check_map<M: HashMixer + Clone>(mixer: M) {
+    let mut map = AutoHashMap::with_mixer(mixer);
+    for i in 0..1000 {
+        assert_eq!(map.insert(U64Hash(i), i), None);
+    }
+    assert_eq!(map.len(), 1000);
+    for i in 0..1000 {
+        assert_eq!(map.get(&U64Hash(i)), Some(&i));
+    }
+
+    // The raw entry API takes unmixed self-hashes.
+    let key = U64Hash(500);
+    let hash = key.get_hash();
+    assert_eq!(
+        map.raw_entry().from_hash(hash, |k| *k == key),
+        Some((&key, &500))
+    );
+    map.raw_entry_mut()
+        .from_key(&U64Hash(1000))
+        .or_insert(U64Hash(1000), 1000);
+    assert_eq!(map[&U64Hash(1000)], 1000);
+
+    map.entry(U64Hash(2000)).or_insert(2000);
+    *map.entry(U64Hash(0)).or_insert(0) += 1;
+    assert_eq!(map[&U64Hash(0)], 1);
+
+    let clone = map.clone();
+    assert_eq!(clone, map);
+
+    for i in 0..500 {
+        assert_eq!(map.remove(&U64Hash(i)), Some(i + (i == 0) as u64));
+    }
+    map.shrink_to_fit();
+    for i in 500..1000 {
+        assert_eq!(map.get(&U64Hash(i)), Some(&i));
+    }
+
+    let mut collected = AutoHashMap::with_mixer(map.mixer().clone());
+    collected.extend(clone);
+    assert_eq!(collected.len(), 1002);
+}
+
+#[test]
+fn maps() {
+    check_map(NoMix);
+    check_map(FibonacciMix);
+    check_map(Murmur3Mix);
+    check_map(SeededMix::new(0xdead_beef));
+    check_map(KeyedMix::new(0xdead_beef));
+}
+
+#[test]
+fn sets() {
+    let a: AutoHashSet<_, Murmur3Mix> = (0..10).map(U64Hash).collect();
+    let b: AutoHashSet<_, Murmur3Mix> = (5..15).map(U64Hash).collect();
+    assert_eq!((&a & &b).len(), 5);
+    assert_eq!((&a | &b).len(), 15);
+    assert_eq!((&a ^ &b).len(), 10);
+    assert_eq!((&a - &b).len(), 5);
+    assert!(a.contains(&U64Hash(3)));
+    assert_eq!(a.mixer(), &Murmur3Mix);
+}
+
+#[test]
+fn seeded() {
+    // Attacker-chosen hashes that only differ in their high bits.
+    let keys: Vec<_> = (0..1000u64).map(|i| RawHashed::new(i << 40, i)).collect();
+
+    let buckets = |mixer: &dyn HashMixer| {
+        let buckets: AutoHashSet<u64> = keys
+            .iter()
+            .map(|key| mixer.mix(key.get_hash()) & 0xff)
+            .collect();
+        buckets.len()
+    };
+    assert_eq!(buckets(&NoMix), 1);
+    assert!(buckets(&KeyedMix::new(1)) > 240);
+
+    let mut a = AutoHashMap::with_seed(1);
+    let mut b = AutoHashMap::with_seed(2);
+    for key in &keys {
+        a.insert(*key, key.value);
+        b.insert(*key, key.value);
+    }
+    assert_eq!(a, b);
+    assert!(a.keys().ne(b.keys()));
+    assert_eq!(a.mixer(), &KeyedMix::new(1));
+    assert_eq!(format!("{:?}", a.mixer()), "KeyedMix { .. }");
+
+    let set: AutoHashSet<U64Hash, _> = AutoHashSet::with_seed(3);
+    assert!(set.is_empty());
+}
+
+#[cfg(feature = "getrandom")]
+#[test]
+fn randomized() {
+    let mut map = AutoHashMap::new_randomized();
+    map.insert(U64Hash(1), 1);
+    assert_eq!(map[&U64Hash(1)], 1);
+    assert_ne!(
+        map.mixer(),
+        AutoHashMap::<U64Hash, (), _>::new_randomized().mixer()
+    );
+
+    let set: AutoHashSet<U64Hash, KeyedMix> = (0..10).map(U64Hash).collect();
+    assert_eq!(set.len(), 10);
+}
@@ -0,0 +1,137 @@
+//! Check the persistent map's collision lists, deep paths, and old versions.
+
+use autohash::mix::{Murmur3Mix, NoMix};
+use autohash::persistent::AutoHamt;
+use autohash::{AutoHash, AutoHashMap};
+
+/// A key with a given hash, to place it anywhere in the trie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Hashed(u64, u32);
+
+impl AutoHash for Hashed {
+    fn get_hash(&self) -> u64 {
+        self.0
+    }
+}
+
+#[test]
+fn equal_hashes() {
+    let a = Hashed(7, 1);
+    let b = Hashed(7, 2);
+    let v1 = AutoHamt::new().insert(a, 10).insert(b, 20);
+    assert_eq!(v1.len(), 2);
+    assert_eq!(v1.get(&a), Some(&10));
+    assert_eq!(v1.get(&b), Some(&20));
+    assert_eq!(v1.get(&Hashed(7, 3)), None);
+
+    // Replacing a value in the collision list leaves the old version alone.
+    let v2 = v1.insert(b, 21);
+    assert_eq!(v2.len(), 2);
+    assert_eq!(v2.get(&b), Some(&21));
+    assert_eq!(v1.get(&b), Some(&20));
+}
+
+#[test]
+fn collapse_collision() {
+    let a = Hashed(7, 1);
+    let b = Hashed(7, 2);
+    // Shares the first level with `a` and `b`, but not the full hash.
+    let c = Hashed(7 + 32, 3);
+    let v1 = AutoHamt::new().insert(a, 10).insert(b, 20).insert(c, 30);
+
+    let v2 = v1.remove(&a);
+    assert_eq!(v2.len(), 2);
+    assert_eq!(v2.get(&a), None);
+    assert_eq!(v2.get(&b), Some(&20));
+    assert_eq!(v2.get(&c), Some(&30));
+
+    // The older version still has both colliding keys.
+    assert_eq!(v1.len(), 3);
+    assert_eq!(v1.get(&a), Some(&10));
+    assert_eq!(v1.get(&b), Some(&20));
+
+    let v3 = v2.remove(&b).remove(&c);
+    assert!(v3.is_empty());
+    assert_eq!(v3, AutoHamt::new());
+    assert_eq!(v2.insert(a, 11).get(&a), Some(&11));
+}
+
+#[test]
+fn deep_paths() {
+    // These hashes only differ in their top bits, so they share a path down
+    // through every level but the last.
+    let a = Hashed(1 << 62, 1);
+    let b = Hashed(2 << 62, 2);
+    let v1 = AutoHamt::new().insert(a, 10).insert(b, 20);
+    assert_eq!(v1.get(&a), Some(&10));
+    assert_eq!(v1.get(&b), Some(&20));
+    assert_eq!(v1.get(&Hashed(3 << 62, 3)), None);
+
+    let v2 = v1.remove(&b);
+    assert_eq!(v2.len(), 1);
+    assert_eq!(v2.get(&a), Some(&10));
+    assert_eq!(v1.get(&b), Some(&20));
+}
+
+#[test]
+fn old_versions() {
+    let mut versions = vec![AutoHamt::new()];
+    for i in 0..300 {
+        let next = versions.last().unwrap().insert(i, i * 2);
+        versions.push(next);
+    }
+    for i in 0..300 {
+        let next = versions.last().unwrap().remove(&(i * 7 % 300));
+        versions.push(next);
+    }
+
+    // Version `n` has the first `n` keys, and later ones lost some again.
+    for (n, hamt) in versions[..=300].iter().enumerate() {
+        assert_eq!(hamt.len(), n);
+        assert!((0..n as u32).all(|i| hamt.get(&i) == Some(&(i * 2))));
+        assert_eq!(hamt.get(&(n as u32)), None);
+    }
+    for (n, hamt) in versions[300..].iter().enumerate() {
+        let removed = |k: u32| (0..n as u32).any(|i| i * 7 % 300 == k);
+        assert_eq!(hamt.len(), 300 - n);
+        assert!((0..300).all(|k| hamt.contains_key(&k) != removed(k)));
+    }
+}
+
+#[test]
+fn remove_to_empty() {
+    let key = |i: u32| Hashed(u64::from(i % 4) << 62, i);
+    let mut hamt = (0..100).map(|i| (key(i), i)).collect::<AutoHamt<_, _>>();
+    assert_eq!(hamt.len(), 100);
+    for i in 0..100 {
+        let next = hamt.remove(&key(i));
+        assert_eq!(next.len(), 99 - i as usize);
+        assert!(!next.contains_key(&key(i)));
+        assert!(hamt.contains_key(&key(i)));
+        hamt = next;
+    }
+    assert!(hamt.is_empty());
+    assert_eq!(hamt.iter().next(), None);
+    assert_eq!(hamt, AutoHamt::new());
+
+    // Removing a missing key shares the same version.
+    let same = hamt.remove(&key(0));
+    assert!(same.ptr_eq(&hamt));
+}
+
+#[test]
+fn conversions() {
+    let map: AutoHashMap<u64, String, Murmur3Mix> = (0..1000).map(|i| (i, i.to_string())).collect();
+    let hamt = AutoHamt::from(map.clone());
+    assert_eq!(hamt.len(), 1000);
+    assert_eq!(hamt[&500], "500");
+    assert_eq!(AutoHashMap::from(&hamt), map);
+
+    let mut keys: Vec<_> = hamt.keys().copied().collect();
+    keys.sort();
+    assert_eq!(keys, (0..1000).collect::<Vec<_>>());
+    assert_eq!(hamt.values().len(), 1000);
+
+    let default: AutoHamt<u64, String, NoMix> = AutoHamt::default();
+    assert!(default.is_empty());
+}
@@ -0,0 +1,147 @@
+//! Check hash-partitioned parallel collection against sequential collection.
+
+#![cfg(feature = "rayon")]
+
+use autohash::mix::{Murmur3Mix, SeededMix};
+use autohash::wrappers::U64Hash;
+use autohash::{AutoHash, AutoHashMap, AutoHashSet};
+use rayon_dep::iter::{
+    IntoParallelIterator, IntoParallelRefIterator, ParallelExtend, ParallelIterator,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn map_collect() {
+    // Every key repeats, so the last value must win like a sequential collect.
+    let items: Vec<(u32, usize)> = (0..100_000).map(|i| (i as u32 % 1000, i)).collect();
+    let seq: AutoHashMap<u32, usize> = items.iter().copied().collect();
+    let par: AutoHashMap<u32, usize> = items.into_par_iter().collect();
+    assert_eq!(par.len(), 1000);
+    assert_eq!(par, seq);
+}
+
+#[test]
+fn map_extend() {
+    let mut seq: AutoHashMap<U64Hash, u64, Murmur3Mix> = AutoHashMap::default();
+    seq.extend((0..500).map(|i| (U64Hash(i), 0)));
+    let mut par = seq.clone();
+
+    let items: Vec<(U64Hash, u64)> = (250..1000).map(|i| (U64Hash(i), i)).collect();
+    seq.extend(items.iter().map(|(k, v)| (*k, *v)));
+    par.par_extend(items.par_iter().map(|(k, v)| (k, v)));
+    assert_eq!(par, seq);
+
+    par.par_extend(items.into_par_iter().map(|(k, v)| (k, v + 1)));
+    assert_eq!(par[&U64Hash(999)], 1000);
+    assert_eq!(par[&U64Hash(0)], 0);
+    assert_eq!(par.len(), 1000);
+}
+
+#[test]
+fn map_collisions() {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Same(u32);
+
+    impl AutoHash for Same {
+        fn get_hash(&self) -> u64 {
+            42
+        }
+    }
+
+    let par: AutoHashMap<Same, u32> = (0..200)
+        .into_par_iter()
+        .map(|i| (Same(i % 100), i))
+        .collect();
+    assert_eq!(par.len(), 100);
+    assert!(par.iter().all(|(k, &v)| v == k.0 + 100));
+}
+
+#[test]
+fn map_hashes_once() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(PartialEq, Eq)]
+    struct Counted(u64);
+
+    impl AutoHash for Counted {
+        fn get_hash(&self) -> u64 {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            self.0.get_hash()
+        }
+    }
+
+    let map: AutoHashMap<Counted, ()> = (0..10_000)
+        .into_par_iter()
+        .map(|i| (Counted(i), ()))
+        .collect();
+    assert_eq!(map.len(), 10_000);
+    assert_eq!(CALLS.load(Ordering::Relaxed), 10_000);
+}
+
+#[test]
+fn set_collect() {
+    let seq: AutoHashSet<u32> = (0..50_000).map(|i| i % 7919).collect();
+    let par: AutoHashSet<u32> = (0..50_000).into_par_iter().map(|i| i % 7919).collect();
+    assert_eq!(par, seq);
+
+    let mut par = AutoHashSet::new();
+    let items: Vec<u32> = (0..1000).collect();
+    par.par_extend(items.par_iter());
+    par.par_extend(items.par_iter());
+    assert_eq!(par, items.iter().copied().collect());
+}
+
+#[test]
+fn map_par_merge() {
+    let maps: Vec<AutoHashMap<u32, Vec<usize>>> = (0..8)
+        .map(|i| (0..1000).map(|k| (k * (i + 1), vec![i as usize])).collect())
+        .collect();
+
+    let mut seq = AutoHashMap::new();
+    for map in maps.clone() {
+        seq.merge_with(map, |_, mut a, b| {
+            a.extend(b);
+            a
+        });
+    }
+
+    let par = AutoHashMap::par_merge(maps, |_, mut a, b| {
+        a.extend(b);
+        a
+    });
+    assert_eq!(par, seq);
+    assert_eq!(par[&0], (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+fn map_par_merge_keeps_first() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Counted(u64);
+
+    impl AutoHash for Counted {
+        fn get_hash(&self) -> u64 {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            self.0.get_hash()
+        }
+    }
+
+    let maps: Vec<AutoHashMap<Counted, u64, SeededMix>> = (0..4)
+        .map(|i| {
+            let mut map = AutoHashMap::with_capacity_and_mixer(300, SeededMix::new(i));
+            map.extend((0..100).map(|k| (Counted(k + i * 50), 1)));
+            map
+        })
+        .collect();
+
+    // The first map has room for every key, so only the keys of the later
+    // maps are hashed, once each.
+    CALLS.store(0, Ordering::Relaxed);
+    let merged = AutoHashMap::par_merge(maps, |_, a, b| a + b);
+    assert_eq!(CALLS.load(Ordering::Relaxed), 300);
+    assert_eq!(merged.mixer(), &SeededMix::new(0));
+    assert_eq!(merged.len(), 250);
+    assert_eq!(merged[&Counted(75)], 2);
+    assert_eq!(merged.values().sum::<u64>(), 400);
+}
@@ -0,0 +1,62 @@
+//! Check parallel removal by predicate against the sequential versions.
+
+#![cfg(feature = "rayon")]
+
+use autohash::{AutoHashMap, AutoHashSet};
+use rayon_dep::iter::ParallelIterator;
+
+#[test]
+fn map_par_retain() {
+    let mut par: AutoHashMap<u32, u32> = (0..10_000).map(|i| (i, i)).collect();
+    let mut seq = par.clone();
+
+    par.par_retain(|&k, v| {
+        *v += 1;
+        k % 3 == 0
+    });
+    seq.retain(|&k, v| {
+        *v += 1;
+        k % 3 == 0
+    });
+    assert_eq!(par, seq);
+    assert_eq!(par.len(), 3334);
+
+    par.insert(1, 1);
+    assert_eq!(par[&1], 1);
+}
+
+#[test]
+fn map_par_drain_filter() {
+    let mut map: AutoHashMap<u32, u32> = (0..10_000).map(|i| (i, i * 2)).collect();
+
+    let mut drained: Vec<_> = map.par_drain_filter(|&k, _| k % 2 == 1).collect();
+    drained.sort();
+    assert_eq!(
+        drained,
+        (0..5000)
+            .map(|i| (2 * i + 1, 4 * i + 2))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(map.len(), 5000);
+    assert!(map.iter().all(|(k, v)| k % 2 == 0 && *v == k * 2));
+
+    // Entries are removed even if the iterator is dropped.
+    drop(map.par_drain_filter(|&k, _| k < 100));
+    assert_eq!(map.len(), 4950);
+}
+
+#[test]
+fn set_par_retain() {
+    let mut set: AutoHashSet<u32> = (0..10_000).collect();
+    set.par_retain(|&x| x % 10 == 0);
+    assert_eq!(set.len(), 1000);
+    assert!(set.iter().all(|x| x % 10 == 0));
+}
+
+#[test]
+fn set_par_drain_filter() {
+    let mut set: AutoHashSet<u32> = (0..10_000).collect();
+    let drained: AutoHashSet<u32> = set.par_drain_filter(|&x| x >= 5000).collect();
+    assert_eq!(drained, (5000..10_000).collect());
+    assert_eq!(set, (0..5000).collect());
+}
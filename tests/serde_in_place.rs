@@ -0,0 +1,34 @@
+//! Check that in-place deserialization reuses the existing table.
+
+#![cfg(feature = "serde")]
+
+use autohash::{AutoHashMap, AutoHashSet};
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+use serde::Deserialize;
+
+#[test]
+fn map() {
+    let mut map: AutoHashMap<u32, u32> = (0..100).map(|i| (i, i)).collect();
+    let capacity = map.capacity();
+
+    for round in 0..3 {
+        let input = MapDeserializer::<_, Error>::new((0..50).map(|i| (i, i + round)));
+        Deserialize::deserialize_in_place(input, &mut map).unwrap();
+        assert_eq!(map.len(), 50);
+        assert_eq!(map.capacity(), capacity);
+        assert!((0..50).all(|i| map[&i] == i + round));
+    }
+}
+
+#[test]
+fn set() {
+    let mut set: AutoHashSet<u32> = (0..100).collect();
+    let capacity = set.capacity();
+
+    for round in 0..3 {
+        let input = SeqDeserializer::<_, Error>::new((0..50).map(|i| i * round));
+        Deserialize::deserialize_in_place(input, &mut set).unwrap();
+        assert_eq!(set.len(), if round == 0 { 1 } else { 50 });
+        assert_eq!(set.capacity(), capacity);
+    }
+}
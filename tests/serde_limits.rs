@@ -0,0 +1,71 @@
+//! Check deserialization with allocation limits.
+
+#![cfg(feature = "serde")]
+
+use autohash::serde::limits::DeserializeConfig;
+use autohash::{AutoHashMap, AutoHashSet};
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+use serde::de::DeserializeSeed;
+
+/// An iterator that claims to be much longer than it is.
+struct Lying<I>(I);
+
+impl<I: Iterator> Iterator for Lying<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (1_000_000, Some(1_000_000))
+    }
+}
+
+#[test]
+fn preallocate() {
+    let input = SeqDeserializer::<_, Error>::new(Lying(0..3));
+    let set = AutoHashSet::<u32>::deserialize_with_limits(input, DeserializeConfig::new()).unwrap();
+    assert_eq!(set.len(), 3);
+    assert!(set.capacity() >= 4096 && set.capacity() < 1_000_000);
+
+    let config = DeserializeConfig::new().max_preallocate(10);
+    let input = SeqDeserializer::<_, Error>::new(Lying(0..3));
+    let set = AutoHashSet::<u32>::deserialize_with_limits(input, config).unwrap();
+    assert_eq!(set.len(), 3);
+    assert!(set.capacity() >= 10 && set.capacity() < 4096);
+
+    let config = DeserializeConfig::new().max_len(3);
+    let input = SeqDeserializer::<_, Error>::new(Lying(0..3));
+    let set = AutoHashSet::<u32>::deserialize_with_limits(input, config).unwrap();
+    assert_eq!(set.len(), 3);
+    assert!(set.capacity() < 10);
+}
+
+#[test]
+fn max_len() {
+    let config = DeserializeConfig::new().max_len(2);
+
+    let input = MapDeserializer::<_, Error>::new(vec![(1, 'a'), (2, 'b')].into_iter());
+    let map = AutoHashMap::<u8, char>::deserialize_with_limits(input, config).unwrap();
+    assert_eq!(map.len(), 2);
+
+    let input = MapDeserializer::<_, Error>::new(vec![(1, 'a'), (2, 'b'), (3, 'c')].into_iter());
+    let error = AutoHashMap::<u8, char>::deserialize_with_limits(input, config).unwrap_err();
+    assert_eq!(error.to_string(), "too many entries, expected at most 2");
+
+    // Duplicates still count against the limit.
+    let input = MapDeserializer::<_, Error>::new(vec![(1, 'a'), (1, 'b'), (1, 'c')].into_iter());
+    let error = AutoHashMap::<u8, char>::deserialize_with_limits(input, config).unwrap_err();
+    assert_eq!(error.to_string(), "too many entries, expected at most 2");
+}
+
+#[test]
+fn seed() {
+    let config = DeserializeConfig::new().max_len(3);
+    let input = MapDeserializer::<_, Error>::new(vec![(1, 'a'), (2, 'b')].into_iter());
+    let seed = config.seed::<AutoHashMap<u8, char>>();
+    let map = seed.deserialize(input).unwrap();
+    assert_eq!(map[&1], 'a');
+    assert_eq!(map[&2], 'b');
+}
@@ -0,0 +1,82 @@
+//! Check maps serialized as a sequence of pairs.
+
+#![cfg(feature = "serde")]
+
+use autohash::serde::pairs;
+use autohash::wrappers::U64Hash;
+use autohash::{AutoHash, AutoHashMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_test::{assert_de_tokens, assert_tokens, Token};
+
+#[derive(Debug)]
+struct Pairs<K, V>(AutoHashMap<K, V>);
+
+impl<K: Eq + AutoHash, V: PartialEq> PartialEq for Pairs<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for Pairs<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        pairs::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for Pairs<K, V>
+where
+    K: Deserialize<'de> + Eq + AutoHash,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        pairs::deserialize(deserializer).map(Pairs)
+    }
+}
+
+#[test]
+fn tuple_keys() {
+    let mut map = AutoHashMap::new();
+    map.insert((1u8, 2u8), "a");
+
+    assert_tokens(
+        &Pairs(map),
+        &[
+            Token::Seq { len: Some(1) },
+            Token::Tuple { len: 2 },
+            Token::Tuple { len: 2 },
+            Token::U8(1),
+            Token::U8(2),
+            Token::TupleEnd,
+            Token::BorrowedStr("a"),
+            Token::TupleEnd,
+            Token::SeqEnd,
+        ],
+    );
+}
+
+#[test]
+fn duplicates() {
+    let mut map = AutoHashMap::new();
+    map.insert(U64Hash(1), 'c');
+    map.insert(U64Hash(2), 'b');
+
+    assert_de_tokens(
+        &Pairs(map),
+        &[
+            Token::Seq { len: Some(3) },
+            Token::Tuple { len: 2 },
+            Token::U64(1),
+            Token::Char('a'),
+            Token::TupleEnd,
+            Token::Tuple { len: 2 },
+            Token::U64(2),
+            Token::Char('b'),
+            Token::TupleEnd,
+            Token::Tuple { len: 2 },
+            Token::U64(1),
+            Token::Char('c'),
+            Token::TupleEnd,
+            Token::SeqEnd,
+        ],
+    );
+}
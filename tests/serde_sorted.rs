@@ -0,0 +1,127 @@
+//! Check that sorted serialization is independent of the table layout.
+
+#![cfg(feature = "serde")]
+
+use autohash::serde::{hash_sorted, sorted};
+use autohash::{AutoHash, AutoHashMap, AutoHashSet};
+use serde::{Serialize, Serializer};
+use serde_test::{assert_ser_tokens, Token};
+
+struct Sorted<T>(T);
+
+impl<T: sorted::SerializeSorted> Serialize for Sorted<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        sorted::serialize(&self.0, serializer)
+    }
+}
+
+struct HashSorted<T>(T);
+
+impl<T: sorted::SerializeSorted> Serialize for HashSorted<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        hash_sorted::serialize(&self.0, serializer)
+    }
+}
+
+fn maps() -> (AutoHashMap<u32, char>, AutoHashMap<u32, char>) {
+    let mut a = AutoHashMap::new();
+    a.insert(3, 'c');
+    a.insert(1, 'a');
+    a.insert(2, 'b');
+
+    let mut b = AutoHashMap::with_capacity(100);
+    for i in 0..50 {
+        b.insert(i + 10, '?');
+    }
+    b.insert(2, 'b');
+    b.insert(1, 'a');
+    b.insert(3, 'c');
+    b.retain(|&k, _| k < 10);
+    (a, b)
+}
+
+#[test]
+fn map() {
+    let (a, b) = maps();
+    let tokens = [
+        Token::Map { len: Some(3) },
+        Token::U32(1),
+        Token::Char('a'),
+        Token::U32(2),
+        Token::Char('b'),
+        Token::U32(3),
+        Token::Char('c'),
+        Token::MapEnd,
+    ];
+    assert_ser_tokens(&Sorted(a), &tokens);
+    assert_ser_tokens(&Sorted(b), &tokens);
+}
+
+#[test]
+fn map_by_hash() {
+    let (a, b) = maps();
+    let mut keys = vec![1u32, 2, 3];
+    keys.sort_by_key(AutoHash::get_hash);
+
+    let mut tokens = vec![Token::Map { len: Some(3) }];
+    for &k in &keys {
+        tokens.push(Token::U32(k));
+        tokens.push(Token::Char(a[&k]));
+    }
+    tokens.push(Token::MapEnd);
+    assert_ser_tokens(&HashSorted(a), &tokens);
+    assert_ser_tokens(&HashSorted(b), &tokens);
+}
+
+#[test]
+fn set() {
+    let a: AutoHashSet<i8> = [5, -1, 3].iter().copied().collect();
+    let b: AutoHashSet<i8, _> = {
+        let mut b = AutoHashSet::with_seed(42);
+        b.extend(&[3, 5, -1]);
+        b
+    };
+    let tokens = [
+        Token::Seq { len: Some(3) },
+        Token::I8(-1),
+        Token::I8(3),
+        Token::I8(5),
+        Token::SeqEnd,
+    ];
+    assert_ser_tokens(&Sorted(a), &tokens);
+    assert_ser_tokens(&Sorted(b), &tokens);
+}
+
+#[test]
+fn set_by_hash() {
+    // Every key has the same hash, so they are ordered by value.
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Same(u8);
+
+    impl Serialize for Same {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_newtype_struct("Same", &self.0)
+        }
+    }
+
+    impl AutoHash for Same {
+        fn get_hash(&self) -> u64 {
+            0
+        }
+    }
+
+    let set: AutoHashSet<Same> = (0..3).rev().map(Same).collect();
+    assert_ser_tokens(
+        &HashSorted(set),
+        &[
+            Token::Seq { len: Some(3) },
+            Token::NewtypeStruct { name: "Same" },
+            Token::U8(0),
+            Token::NewtypeStruct { name: "Same" },
+            Token::U8(1),
+            Token::NewtypeStruct { name: "Same" },
+            Token::U8(2),
+            Token::SeqEnd,
+        ],
+    );
+}
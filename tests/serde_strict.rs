@@ -0,0 +1,58 @@
+//! Check that strict deserialization rejects duplicate keys.
+
+#![cfg(feature = "serde")]
+
+use autohash::mix::Murmur3Mix;
+use autohash::serde::strict;
+use autohash::wrappers::U64Hash;
+use autohash::{AutoHashMap, AutoHashSet};
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+use serde::Deserialize;
+use serde_test::{assert_ser_tokens, Token};
+
+#[test]
+fn map() {
+    let entries = vec![(1, 'a'), (2, 'b')];
+    let de = MapDeserializer::<_, Error>::new(entries.into_iter());
+    let map: AutoHashMap<i32, char> = strict::deserialize(de).unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&2], 'b');
+
+    let entries = vec![(1, 'a'), (2, 'b'), (2, 'c')];
+    let de = MapDeserializer::<_, Error>::new(entries.clone().into_iter());
+    let error = strict::deserialize::<_, AutoHashMap<i32, char>>(de).unwrap_err();
+    assert_eq!(error.to_string(), "duplicate map key 2");
+
+    // The default implementation keeps the last value.
+    let de = MapDeserializer::<_, Error>::new(entries.into_iter());
+    let map = AutoHashMap::<i32, char>::deserialize(de).unwrap();
+    assert_eq!(map[&2], 'c');
+}
+
+#[test]
+fn set() {
+    let de = SeqDeserializer::<_, Error>::new(vec![5u64, 6, 7].into_iter());
+    let set: AutoHashSet<U64Hash, Murmur3Mix> = strict::deserialize(de).unwrap();
+    assert_eq!(set.len(), 3);
+
+    let de = SeqDeserializer::<_, Error>::new(vec![5u64, 6, 5].into_iter());
+    let error = strict::deserialize::<_, AutoHashSet<U64Hash>>(de).unwrap_err();
+    assert_eq!(error.to_string(), "duplicate set element 5");
+}
+
+#[test]
+fn serialize() {
+    struct Strict<'a>(&'a AutoHashSet<i32>);
+
+    impl serde::Serialize for Strict<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            strict::serialize(self.0, serializer)
+        }
+    }
+
+    let set: AutoHashSet<i32> = Some(1).into_iter().collect();
+    assert_ser_tokens(
+        &Strict(&set),
+        &[Token::Seq { len: Some(1) }, Token::I32(1), Token::SeqEnd],
+    );
+}
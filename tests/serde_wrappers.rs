@@ -0,0 +1,81 @@
+//! Check the serde implementations for the wrapper key types.
+
+#![cfg(feature = "serde")]
+
+use autohash::serde::checked;
+use autohash::wrappers::{
+    AutoHashed, LazyHashed, MemoHashed, RawHashed, SyncLazyHashed, U128Hash, U64Hash,
+};
+use autohash::{AutoHash, AutoHashMap};
+use serde::de::value::{Error, SeqDeserializer, StrDeserializer, U128Deserializer};
+use serde::Deserialize;
+use serde_test::{assert_tokens, Token};
+use std::collections::hash_map::DefaultHasher;
+
+#[test]
+fn hash_wrappers() {
+    assert_tokens(&U64Hash(5), &[Token::U64(5)]);
+    let de = U128Deserializer::<Error>::new(5);
+    assert_eq!(U128Hash::deserialize(de), Ok(U128Hash(5)));
+}
+
+#[test]
+fn value_wrappers() {
+    let auto: AutoHashed<String, DefaultHasher> = "a".to_string().into();
+    let memo: MemoHashed<String, DefaultHasher> = "a".to_string().into();
+    let lazy: LazyHashed<String, DefaultHasher> = "a".to_string().into();
+    let sync: SyncLazyHashed<String, DefaultHasher> = "a".to_string().into();
+    assert_tokens(&auto, &[Token::Str("a")]);
+    assert_tokens(&memo, &[Token::Str("a")]);
+    assert_tokens(&lazy, &[Token::Str("a")]);
+    assert_tokens(&sync, &[Token::Str("a")]);
+
+    // The hash is recomputed, not read from the input.
+    let de = StrDeserializer::<Error>::new("a");
+    let memo: MemoHashed<String, DefaultHasher> = Deserialize::deserialize(de).unwrap();
+    assert_eq!(memo.get_hash(), auto.get_hash());
+}
+
+#[test]
+fn raw_hashed() {
+    let raw = RawHashed::new(7, 'x');
+    let tokens = [
+        Token::Tuple { len: 2 },
+        Token::U64(7),
+        Token::Char('x'),
+        Token::TupleEnd,
+    ];
+    assert_tokens(&raw, &tokens);
+
+    // The default deserializer trusts any hash.
+    let forged = SeqDeserializer::<_, Error>::new(vec![0, 5u64].into_iter());
+    let raw: RawHashed<u64> = Deserialize::deserialize(forged).unwrap();
+    assert_eq!(raw.get_hash(), 0);
+}
+
+#[test]
+fn raw_hashed_checked() {
+    let good = SeqDeserializer::<_, Error>::new(vec![5u64.get_hash(), 5].into_iter());
+    let raw: RawHashed<u64> = checked::deserialize(good).unwrap();
+    assert_eq!(raw.value, 5u64);
+    assert_eq!(raw.get_hash(), 5u64.get_hash());
+
+    let forged = SeqDeserializer::<_, Error>::new(vec![0, 5u64].into_iter());
+    let err = checked::deserialize::<_, u64>(forged).unwrap_err();
+    assert!(err.to_string().contains("invalid hash 0x0"));
+}
+
+#[test]
+fn map_keys() {
+    let mut map = AutoHashMap::new();
+    map.insert(U64Hash(1), 'a');
+    assert_tokens(
+        &map,
+        &[
+            Token::Map { len: Some(1) },
+            Token::U64(1),
+            Token::Char('a'),
+            Token::MapEnd,
+        ],
+    );
+}
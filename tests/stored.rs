@@ -0,0 +1,96 @@
+//! Check that stored-hash maps and sets never rehash their keys.
+
+use autohash::mix::Murmur3Mix;
+use autohash::stored::{StoredHashMap, StoredHashSet};
+use autohash::{AutoHash, AutoHashMap};
+use std::cell::Cell;
+
+thread_local! {
+    static HASHES: Cell<usize> = const { Cell::new(0) };
+}
+
+fn hashes() -> usize {
+    HASHES.with(|count| count.replace(0))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Counted(u64);
+
+impl AutoHash for Counted {
+    fn get_hash(&self) -> u64 {
+        HASHES.with(|count| count.set(count.get() + 1));
+        self.0.get_hash()
+    }
+}
+
+#[test]
+fn map() {
+    hashes();
+    let mut map = StoredHashMap::new();
+    for i in 0..1000 {
+        assert_eq!(map.insert(Counted(i), i), None);
+    }
+    assert_eq!(hashes(), 1000);
+
+    map.reserve(10_000);
+    map.retain(|k, _| k.0 % 2 == 0);
+    map.shrink_to_fit();
+    map.shrink_to(0);
+    assert_eq!(hashes(), 0);
+    assert_eq!(map.len(), 500);
+
+    for i in 0..1000 {
+        assert_eq!(map.get(&Counted(i)), (i % 2 == 0).then_some(&i));
+    }
+    assert_eq!(hashes(), 1000);
+
+    *map.get_mut(&Counted(2)).unwrap() += 1;
+    assert_eq!(map[&Counted(2)], 3);
+    assert_eq!(map.remove_entry(&Counted(2)), Some((Counted(2), 3)));
+    assert_eq!(map.remove(&Counted(2)), None);
+
+    let clone = map.clone();
+    hashes();
+    assert_eq!(clone, map);
+    assert_eq!(hashes(), 0);
+    let mut values: Vec<_> = map.into_iter().map(|(_, v)| v).collect();
+    values.sort();
+    assert_eq!(values.len(), 499);
+    assert_eq!(values[0], 0);
+}
+
+#[test]
+fn compare_auto() {
+    let mut auto = AutoHashMap::new();
+    let mut stored = StoredHashMap::new();
+    hashes();
+    for i in 0..1000 {
+        auto.insert(Counted(i), ());
+    }
+    let auto_hashes = hashes();
+    for i in 0..1000 {
+        stored.insert(Counted(i), ());
+    }
+    assert!(auto_hashes > 1000);
+    assert_eq!(hashes(), 1000);
+}
+
+#[test]
+fn set() {
+    let mut set: StoredHashSet<u64, Murmur3Mix> = (0..100).collect();
+    assert_eq!(set.len(), 100);
+    assert!(set.contains(&42));
+    assert!(!set.insert(42));
+    assert_eq!(set.take(&42), Some(42));
+    assert!(!set.remove(&42));
+    assert_eq!(set.get(&7), Some(&7));
+    assert_eq!(set.mixer(), &Murmur3Mix);
+
+    let clone = set.clone();
+    assert_eq!(clone, set);
+    let mut drained: Vec<_> = set.drain().collect();
+    drained.sort();
+    assert_eq!(drained.len(), 99);
+    assert!(set.is_empty());
+    assert_eq!(format!("{:?}", StoredHashSet::<u64>::new()), "{}");
+}
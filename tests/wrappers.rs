@@ -0,0 +1,198 @@
+//! Check the caching behavior of the hashing wrappers.
+
+use autohash::wrappers::{AutoHashed, LazyHashed, SyncLazyHashed};
+use autohash::{AutoHash, AutoHashMap};
+use std::cell::Cell;
+use std::hash::{Hash, Hasher};
+
+thread_local! {
+    static HASHES: Cell<usize> = const { Cell::new(0) };
+}
+
+fn hashes() -> usize {
+    HASHES.with(|count| count.replace(0))
+}
+
+/// An FNV-1a `Hasher` that counts how many times it was created.
+struct CountingHasher(u64);
+
+impl Default for CountingHasher {
+    fn default() -> Self {
+        HASHES.with(|count| count.set(count.get() + 1));
+        CountingHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for CountingHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+}
+
+/// A value whose hash is always 0, the `SyncLazyHashed` sentinel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Zero;
+
+impl Hash for Zero {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+#[derive(Default)]
+struct ZeroHasher;
+
+impl Hasher for ZeroHasher {
+    fn finish(&self) -> u64 {
+        0
+    }
+    fn write(&mut self, _: &[u8]) {}
+}
+
+#[test]
+fn lazy() {
+    hashes();
+    let key: LazyHashed<String, CountingHasher> = "key".to_string().into();
+    assert_eq!(hashes(), 0);
+
+    let hash = key.get_hash();
+    assert_eq!(key.get_hash(), hash);
+    assert_eq!(hashes(), 1);
+
+    let clone = key.clone();
+    assert_eq!(clone.get_hash(), hash);
+    assert_eq!(hashes(), 0);
+    assert_eq!(clone, key);
+    assert_eq!(format!("{:?}", clone), "\"key\"");
+
+    let query: AutoHashed<&str, CountingHasher> = "key".into();
+    assert_eq!(query.get_hash(), hash);
+
+    let mut map = AutoHashMap::new();
+    map.insert(key, 1);
+    assert_eq!(map.get(&query), Some(&1));
+}
+
+#[test]
+fn sync_lazy() {
+    hashes();
+    let key: SyncLazyHashed<String, CountingHasher> = "key".to_string().into();
+    assert_eq!(hashes(), 0);
+
+    let hash = key.get_hash();
+    assert_eq!(key.get_hash(), hash);
+    assert_eq!(hashes(), 1);
+
+    let mut clone: SyncLazyHashed<String, CountingHasher> = "other".to_string().into();
+    clone.clone_from(&key);
+    assert_eq!(clone.get_hash(), hash);
+    assert_eq!(hashes(), 0);
+    assert_eq!(clone, key);
+
+    let query: AutoHashed<&str, CountingHasher> = "key".into();
+    let mut map = AutoHashMap::new();
+    map.insert(key, 1);
+    assert_eq!(map.get(&query), Some(&1));
+
+    // A real hash of 0 can't be cached, but it's still correct.
+    let zero: SyncLazyHashed<Zero, ZeroHasher> = Zero.into();
+    assert_eq!(zero.get_hash(), 0);
+    assert_eq!(zero.get_hash(), 0);
+}
+
+#[test]
+fn sync_lazy_threads() {
+    let key: SyncLazyHashed<String, CountingHasher> = "key".to_string().into();
+    let hash = key.get_hash();
+    let key = &key;
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(move || assert_eq!(key.get_hash(), hash));
+        }
+    });
+}
+
+#[test]
+fn by_address() {
+    use autohash::wrappers::ByAddress;
+    use autohash::AutoHashSet;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let a = Rc::new(1);
+    let b = Rc::new(1);
+    assert_eq!(a, b);
+    assert_ne!(ByAddress(a.clone()), ByAddress(b.clone()));
+    assert_eq!(ByAddress(a.clone()), ByAddress(a.clone()));
+    assert_eq!(ByAddress(a.clone()).get_hash(), ByAddress(&*a).get_hash());
+    assert_eq!(format!("{:?}", ByAddress(a.clone())), "1");
+
+    let nodes: Vec<Arc<str>> = vec!["x".into(), "x".into(), "y".into()];
+    let set: AutoHashSet<_> = nodes.iter().map(|n| ByAddress(n.clone())).collect();
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(&ByAddress(nodes[1].clone())));
+
+    let values = [0u8; 4];
+    let refs: AutoHashSet<_> = values.iter().map(ByAddress).collect();
+    assert_eq!(refs.len(), 4);
+}
+
+#[test]
+fn traits() {
+    use autohash::wrappers::{ByAddress, MemoHashed, RawHashed, U128Hash, U64Hash};
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{BTreeSet, HashSet};
+
+    type Auto = AutoHashed<String, DefaultHasher>;
+    type Memo = MemoHashed<String, DefaultHasher>;
+    type Lazy = LazyHashed<String, DefaultHasher>;
+    type SyncLazy = SyncLazyHashed<String, DefaultHasher>;
+
+    let words = ["b", "c", "a"];
+    let sorted: BTreeSet<Memo> = words.iter().map(|w| w.to_string().into()).collect();
+    let sorted: Vec<_> = sorted.into_iter().map(Memo::into_inner).collect();
+    assert_eq!(sorted, ["a", "b", "c"]);
+
+    let mut lazy: Vec<Lazy> = words.iter().map(|w| w.to_string().into()).collect();
+    lazy.sort();
+    assert_eq!(lazy[0].len(), 1);
+    assert_eq!(format!("{}", lazy[0]), "a");
+    assert_eq!(lazy[0].as_ref(), "a");
+
+    let auto = Auto::from("x".to_string());
+    assert_eq!(*auto, "x");
+    assert_eq!(format!("{}", auto), "x");
+    assert_eq!(Auto::default().into_inner(), "");
+    assert_eq!(Memo::default().get_hash(), Auto::default().get_hash());
+    assert_eq!(SyncLazy::default().get_hash(), Auto::default().get_hash());
+    assert!(SyncLazy::from("a".to_string()) < SyncLazy::from("b".to_string()));
+
+    let raw = RawHashed::new(7, "seven");
+    assert_eq!(raw.get_hash(), 7);
+    assert_eq!(format!("{}", raw), "seven");
+    assert_eq!(RawHashed::<u8>::default().get_hash(), 0);
+    assert_eq!(raw.into_inner(), "seven");
+
+    assert_eq!(U64Hash::default(), U64Hash(0));
+    assert!(U64Hash(1) < U64Hash(2));
+    assert_eq!(*U64Hash(5), 5);
+    assert_eq!(format!("{}", U128Hash(9)), "9");
+    assert_eq!(U128Hash(9).into_inner(), 9);
+
+    // `Hash` feeds the self-hash to a standard hasher.
+    let std_set: HashSet<U64Hash> = (0..10).map(U64Hash).collect();
+    assert!(std_set.contains(&U64Hash(3)));
+    let std_set: HashSet<Memo> = words.iter().map(|w| w.to_string().into()).collect();
+    assert!(std_set.contains(&Memo::from("a".to_string())));
+
+    let values = [1, 2];
+    let a = ByAddress(&values[0]);
+    let b = ByAddress(&values[1]);
+    assert!(a < b);
+    assert_eq!(format!("{}", a), "1");
+    assert_eq!(**a, 1);
+    assert_eq!(*a.into_inner(), 1);
+}